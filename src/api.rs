@@ -1,13 +1,37 @@
+use crate::transcript::{Segment, TranscribeOptions, Transcript};
 use reqwest::multipart;
 use std::time::Duration;
 
-/// Send WAV audio to an OpenAI-compatible transcription endpoint and return the text.
+/// Endpoint for a request: `/audio/translations` translates to English,
+/// `/audio/transcriptions` keeps the spoken language.
+pub fn endpoint_url(base_url: &str, translate: bool) -> String {
+    let path = if translate {
+        "audio/translations"
+    } else {
+        "audio/transcriptions"
+    };
+    format!("{}/{path}", base_url.trim_end_matches('/'))
+}
+
+/// Model to ask for. The turbo variant of large-v3 was not trained to
+/// translate, and Groq's `/audio/translations` rejects it, so translations
+/// use the full large-v3 model instead.
+pub fn request_model(model: &str, translate: bool) -> &str {
+    if translate && model == "whisper-large-v3-turbo" {
+        "whisper-large-v3"
+    } else {
+        model
+    }
+}
+
+/// Send WAV audio to an OpenAI-compatible transcription endpoint and return the transcript.
 pub async fn transcribe(
     base_url: &str,
     api_key: &str,
     model: &str,
     wav_data: Vec<u8>,
-) -> Result<String, String> {
+    options: &TranscribeOptions,
+) -> Result<Transcript, String> {
     // Validate URL scheme — reject file://, ftp://, etc.
     if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
         return Err("Invalid API URL: only http:// and https:// are allowed".into());
@@ -22,17 +46,25 @@ pub async fn transcribe(
         eprintln!("WARNING: API endpoint uses unencrypted HTTP for a remote host");
     }
 
-    let url = format!("{}/audio/transcriptions", base_url.trim_end_matches('/'));
+    let url = endpoint_url(base_url, options.translate);
 
     let file_part = multipart::Part::bytes(wav_data)
         .file_name("audio.wav")
         .mime_str("audio/wav")
         .map_err(|e| format!("Multipart error: {e}"))?;
 
-    let form = multipart::Form::new()
-        .text("model", model.to_string())
-        .text("response_format", "json")
+    let mut form = multipart::Form::new()
+        .text("model", request_model(model, options.translate).to_string())
+        // verbose_json adds segment timings and the detected language
+        .text("response_format", "verbose_json")
         .part("file", file_part);
+    if let Some(prompt) = &options.prompt {
+        form = form.text("prompt", prompt.clone());
+    }
+    // Translations always come out in English and take no language field
+    if let Some(language) = options.language.as_ref().filter(|_| !options.translate) {
+        form = form.text("language", language.clone());
+    }
 
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(120))
@@ -59,8 +91,53 @@ pub async fn transcribe(
         .await
         .map_err(|e| format!("JSON parse error: {e}"))?;
 
-    json["text"]
+    parse_response(&json, options)
+}
+
+/// Build a transcript from a `json` or `verbose_json` response body.
+pub fn parse_response(
+    json: &serde_json::Value,
+    options: &TranscribeOptions,
+) -> Result<Transcript, String> {
+    let text = json["text"]
         .as_str()
-        .map(|s| s.to_string())
-        .ok_or_else(|| format!("No 'text' field in response: {json}"))
+        .map(|s| s.trim().to_string())
+        .ok_or_else(|| format!("No 'text' field in response: {json}"))?;
+
+    // Segment times are in seconds; plain `json` responses have none
+    let seconds =
+        |value: &serde_json::Value| (value.as_f64().unwrap_or(0.0).max(0.0) * 1000.0) as u64;
+    let segments = json["segments"]
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .map(|segment| Segment {
+                    start_ms: seconds(&segment["start"]),
+                    end_ms: seconds(&segment["end"]),
+                    text: segment["text"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // The detected language comes back as a name ("portuguese") or a code.
+    // Translations report their English output, not the spoken language.
+    let language = options.language.clone().or_else(|| {
+        json["language"]
+            .as_str()
+            .filter(|_| !options.translate)
+            .and_then(crate::config::language_code)
+            .map(str::to_string)
+    });
+
+    Ok(Transcript {
+        text,
+        language,
+        segments,
+    })
 }
@@ -1,124 +1,1010 @@
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use std::io::Cursor;
+use crate::audio_source::{AudioSource, CpalSource, SourceStream};
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::collections::VecDeque;
+use std::io::{Read, Seek, Write};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 
-/// Captures audio from the default input device into an in-memory buffer.
+/// RMS level below which a window counts as silence for auto-stop.
+pub const VAD_RMS_THRESHOLD: f32 = 0.01;
+
+/// Length of one voice-activity analysis window.
+const VAD_WINDOW_MS: u32 = 30;
+
+/// Audio host picked in settings; `None` uses the platform default.
+static AUDIO_HOST: Mutex<Option<cpal::HostId>> = Mutex::new(None);
+
+/// Names of the audio hosts compiled in, e.g. "ALSA", plus "JACK" when built
+/// with the `jack` feature.
+pub fn audio_hosts() -> Vec<&'static str> {
+    cpal::available_hosts()
+        .into_iter()
+        .map(|id| id.name())
+        .collect()
+}
+
+/// Capture through the named host from now on. `None` selects the platform
+/// default. Returns false, leaving the selection as it was, when the named
+/// host is not available.
+pub fn set_audio_host(name: Option<&str>) -> bool {
+    let id = match name {
+        Some(name) => match cpal::available_hosts()
+            .into_iter()
+            .find(|id| id.name().eq_ignore_ascii_case(name))
+        {
+            Some(id) => Some(id),
+            None => {
+                eprintln!("Audio host {name:?} not available");
+                return false;
+            }
+        },
+        None => None,
+    };
+    if let Ok(mut host) = AUDIO_HOST.lock() {
+        *host = id;
+    }
+    true
+}
+
+/// The selected audio host, or the platform default when it can't be opened.
+pub fn host() -> cpal::Host {
+    let id = AUDIO_HOST.lock().ok().and_then(|host| *host);
+    if let Some(id) = id {
+        match cpal::host_from_id(id) {
+            Ok(host) => return host,
+            Err(e) => eprintln!("Audio host {} unavailable ({e}), using default", id.name()),
+        }
+    }
+    cpal::default_host()
+}
+
+/// List the names of all input devices on the selected host.
+pub fn input_devices() -> Vec<String> {
+    let host = host();
+    match host.input_devices() {
+        Ok(devices) => devices.filter_map(|d| d.name().ok()).collect(),
+        Err(e) => {
+            eprintln!("Failed to enumerate input devices: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Detects trailing silence after speech in a mono sample stream.
+///
+/// Samples are analysed in short windows; a window whose RMS is above the
+/// threshold counts as speech. Once speech has been heard, the detector fires
+/// after `silence_ms` of consecutive quiet windows.
+pub struct SilenceDetector {
+    threshold: f32,
+    window_len: usize,
+    silence_limit: usize,
+    window_sum_sq: f32,
+    window_fill: usize,
+    heard_speech: bool,
+    trailing_silence: usize,
+}
+
+impl SilenceDetector {
+    pub fn new(sample_rate: u32, threshold: f32, silence_ms: u32) -> Self {
+        let window_len = (sample_rate as usize * VAD_WINDOW_MS as usize / 1000).max(1);
+        let silence_limit = sample_rate as usize * silence_ms as usize / 1000;
+        Self {
+            threshold,
+            window_len,
+            silence_limit,
+            window_sum_sq: 0.0,
+            window_fill: 0,
+            heard_speech: false,
+            trailing_silence: 0,
+        }
+    }
+
+    /// Feed mono samples. Returns `true` once the trailing silence limit is reached.
+    pub fn feed(&mut self, mono: &[f32]) -> bool {
+        for &s in mono {
+            self.window_sum_sq += s * s;
+            self.window_fill += 1;
+            if self.window_fill == self.window_len {
+                let rms = (self.window_sum_sq / self.window_len as f32).sqrt();
+                if rms >= self.threshold {
+                    self.heard_speech = true;
+                    self.trailing_silence = 0;
+                } else if self.heard_speech {
+                    self.trailing_silence += self.window_len;
+                }
+                self.window_sum_sq = 0.0;
+                self.window_fill = 0;
+            }
+        }
+        self.heard_speech && self.trailing_silence >= self.silence_limit
+    }
+}
+
+/// RMS level treated as "nothing is coming in" (about -60 dBFS).
+pub const SILENCE_FLOOR_RMS: f32 = 0.001;
+
+/// Rolling input level published from the audio callback.
+///
+/// Values are stored as `f32` bits so the UI thread can read them without
+/// taking the sample buffer lock.
+#[derive(Default)]
+pub struct InputLevel {
+    rms: AtomicU32,
+    peak: AtomicU32,
+}
+
+impl InputLevel {
+    /// Current smoothed RMS and decaying peak, both in 0.0..=1.0.
+    pub fn get(&self) -> (f32, f32) {
+        (
+            f32::from_bits(self.rms.load(Ordering::Relaxed)),
+            f32::from_bits(self.peak.load(Ordering::Relaxed)),
+        )
+    }
+
+    /// Fold one block of samples into the rolling level.
+    pub fn update(&self, block: &[f32]) {
+        if block.is_empty() {
+            return;
+        }
+        let sum_sq: f32 = block.iter().map(|s| s * s).sum();
+        let rms = (sum_sq / block.len() as f32).sqrt();
+        let peak = block.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+
+        let (prev_rms, prev_peak) = self.get();
+        let rms = prev_rms * 0.7 + rms * 0.3;
+        let peak = peak.max(prev_peak * 0.95);
+        self.rms.store(rms.to_bits(), Ordering::Relaxed);
+        self.peak.store(peak.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.rms.store(0.0f32.to_bits(), Ordering::Relaxed);
+        self.peak.store(0.0f32.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Map a linear level to a 0.0..=1.0 meter position on a -60..0 dBFS scale.
+pub fn meter_fraction(level: f32) -> f32 {
+    if level <= SILENCE_FLOOR_RMS {
+        return 0.0;
+    }
+    let db = 20.0 * level.log10();
+    ((db + 60.0) / 60.0).clamp(0.0, 1.0)
+}
+
+/// In-memory budget for a recording before older samples spill to disk.
+pub const MEMORY_CAP_BYTES: usize = 64 * 1024 * 1024;
+
+/// Seconds of the most recent audio that stay in memory (live preview window),
+/// as long as they fit in half of [`MEMORY_CAP_BYTES`].
+pub const KEEP_IN_MEMORY_SECS: u32 = 30;
+
+/// Interleaved sample storage for one recording.
+///
+/// Samples accumulate in memory up to a cap; past that, everything except the
+/// most recent `keep` samples is moved to an anonymous temp file that the OS
+/// deletes as soon as it is closed, so a runaway recording can't exhaust RAM.
+/// The file is written by a background thread: `push` runs in the realtime
+/// audio callback and must never wait on the disk.
+///
+/// Memory is a queue of fixed-size chunks so that spilling hands whole
+/// chunks to the writer instead of copying the kept tail into a new buffer.
+pub struct CaptureBuffer {
+    memory: std::collections::VecDeque<Vec<f32>>,
+    memory_len: usize,
+    chunk: usize,
+    spill: SpillStore,
+    writer: Option<SpillWriter>,
+    spilled: usize,
+    frame: usize,
+    memory_cap: usize,
+    keep: usize,
+}
+
+/// Spilled audio: the samples in the temp file, then any the writer failed
+/// to write, kept in memory rather than lost.
+#[derive(Default)]
+struct SpillStore {
+    file: Option<std::fs::File>,
+    written: usize,
+    unwritten: Vec<f32>,
+    failed: bool,
+}
+
+impl SpillStore {
+    fn append(&mut self, block: Vec<f32>) {
+        if !self.failed {
+            match self.write(&block) {
+                Ok(()) => {
+                    self.written += block.len();
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("Failed to spill audio to disk: {e}");
+                    self.failed = true;
+                }
+            }
+        }
+        self.unwritten.extend_from_slice(&block);
+    }
+
+    fn write(&mut self, block: &[f32]) -> Result<(), String> {
+        if self.file.is_none() {
+            let file = tempfile::tempfile()
+                .map_err(|e| format!("Failed to create audio spill file: {e}"))?;
+            self.file = Some(file);
+        }
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        let mut bytes = Vec::with_capacity(SPILL_WRITE_SAMPLES * 4);
+        for chunk in block.chunks(SPILL_WRITE_SAMPLES) {
+            bytes.clear();
+            bytes.extend(chunk.iter().flat_map(|s| s.to_le_bytes()));
+            file.write_all(&bytes)
+                .map_err(|e| format!("Spill write error: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Most frames in one in-memory chunk of a [`CaptureBuffer`].
+const CAPTURE_CHUNK_FRAMES: usize = 16384;
+
+/// Samples converted to bytes per write by the spill thread.
+const SPILL_WRITE_SAMPLES: usize = 65536;
+
+/// The thread appending spilled blocks to a [`SpillStore`], which it hands
+/// back when the channel closes.
+struct SpillWriter {
+    tx: std::sync::mpsc::Sender<Vec<f32>>,
+    thread: std::thread::JoinHandle<SpillStore>,
+}
+
+impl CaptureBuffer {
+    /// `memory_cap` and `keep` are in samples (not frames). At most half
+    /// the cap is kept, so a spill always frees a good part of it.
+    pub fn new(channels: u16, memory_cap: usize, keep: usize) -> Self {
+        let frame = channels.max(1) as usize;
+        let memory_cap = memory_cap.max(frame);
+        Self {
+            memory: std::collections::VecDeque::new(),
+            memory_len: 0,
+            chunk: (memory_cap / 8).clamp(frame, frame * CAPTURE_CHUNK_FRAMES) / frame * frame,
+            spill: SpillStore::default(),
+            writer: None,
+            spilled: 0,
+            frame,
+            memory_cap,
+            keep: keep.min(memory_cap / 2) / frame * frame,
+        }
+    }
+
+    /// Total samples recorded, including any spilled to disk.
+    pub fn len(&self) -> usize {
+        self.spilled + self.memory_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Samples currently held in memory (the most recent part of the recording).
+    pub fn recent(&self) -> Vec<f32> {
+        self.recent_tail(usize::MAX)
+    }
+
+    /// The last `max_len` samples held in memory, starting on a frame
+    /// boundary so channels stay aligned.
+    pub fn recent_tail(&self, max_len: usize) -> Vec<f32> {
+        let mut skip = self.memory_len.saturating_sub(max_len) / self.frame * self.frame;
+        let mut tail = Vec::with_capacity(self.memory_len - skip);
+        for chunk in &self.memory {
+            let from = skip.min(chunk.len());
+            tail.extend_from_slice(&chunk[from..]);
+            skip -= from;
+        }
+        tail
+    }
+
+    /// Samples moved out of memory to the temp file.
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    pub fn push(&mut self, mut data: &[f32]) {
+        while !data.is_empty() {
+            let chunk = match self.memory.back_mut() {
+                Some(last) if last.len() < self.chunk => last,
+                _ => {
+                    self.memory.push_back(Vec::with_capacity(self.chunk));
+                    self.memory.back_mut().expect("chunk just pushed")
+                }
+            };
+            let n = data.len().min(self.chunk - chunk.len());
+            chunk.extend_from_slice(&data[..n]);
+            data = &data[n..];
+            self.memory_len += n;
+        }
+        if self.memory_len > self.memory_cap {
+            self.spill_to_disk();
+        }
+    }
+
+    /// Hand everything but the kept tail to the writer thread. Whole chunks
+    /// are moved, not copied; only the one straddling the tail is split.
+    fn spill_to_disk(&mut self) {
+        let mut count = (self.memory_len - self.keep) / self.frame * self.frame;
+        while count > 0 {
+            let Some(front) = self.memory.front_mut() else {
+                break;
+            };
+            let block = if front.len() <= count {
+                self.memory.pop_front().expect("front chunk exists")
+            } else {
+                let rest = front.split_off(count);
+                std::mem::replace(front, rest)
+            };
+            let len = block.len();
+            if !self.send_to_writer(block) {
+                return;
+            }
+            count -= len;
+            self.memory_len -= len;
+            self.spilled += len;
+        }
+        dbg_log!("[AUDIO] spilled samples to disk ({} total)", self.spilled);
+    }
+
+    /// Queue a block for the writer thread, starting it on first use. If
+    /// the thread is gone the block goes back to memory, and the recording
+    /// stays there from then on rather than losing audio.
+    fn send_to_writer(&mut self, block: Vec<f32>) -> bool {
+        let writer = self.writer.get_or_insert_with(|| {
+            let (tx, rx) = std::sync::mpsc::channel::<Vec<f32>>();
+            let mut store = std::mem::take(&mut self.spill);
+            let thread = std::thread::spawn(move || {
+                for block in rx {
+                    store.append(block);
+                }
+                store
+            });
+            SpillWriter { tx, thread }
+        });
+        if let Err(std::sync::mpsc::SendError(block)) = writer.tx.send(block) {
+            eprintln!("Audio spill thread stopped, keeping recording in memory");
+            self.memory.push_front(block);
+            self.memory_cap = usize::MAX;
+            return false;
+        }
+        true
+    }
+
+    /// Wait for the writer thread to write out everything it was given.
+    fn finish_spill(&mut self) -> Result<(), String> {
+        if let Some(SpillWriter { tx, thread }) = self.writer.take() {
+            drop(tx);
+            self.spill = thread
+                .join()
+                .map_err(|_| "Audio spill thread panicked".to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Visit the whole recording in order, in frame-aligned chunks.
+    pub fn for_each_chunk(
+        &mut self,
+        mut f: impl FnMut(&[f32]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        use std::io::SeekFrom;
+
+        self.finish_spill()?;
+        if let Some(file) = self.spill.file.as_mut() {
+            file.seek(SeekFrom::Start(0))
+                .map_err(|e| format!("Spill seek error: {e}"))?;
+            let chunk_samples = self.frame * 16384;
+            let mut bytes = vec![0u8; chunk_samples * 4];
+            let mut remaining = self.spill.written;
+            while remaining > 0 {
+                let n = remaining.min(chunk_samples);
+                file.read_exact(&mut bytes[..n * 4])
+                    .map_err(|e| format!("Spill read error: {e}"))?;
+                let chunk: Vec<f32> = bytes[..n * 4]
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                f(&chunk)?;
+                remaining -= n;
+            }
+            file.seek(SeekFrom::End(0))
+                .map_err(|e| format!("Spill seek error: {e}"))?;
+        }
+        if !self.spill.unwritten.is_empty() {
+            f(&self.spill.unwritten)?;
+        }
+        for chunk in self.memory.iter().filter(|c| !c.is_empty()) {
+            f(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// Receives converted f32 samples from the source callback.
+struct CaptureSink {
+    samples: Arc<Mutex<CaptureBuffer>>,
+    channels: u16,
+    max_samples: Option<usize>,
+    limit_reached: Arc<AtomicBool>,
+    silence: Option<SilenceDetector>,
+    silence_reached: Arc<AtomicBool>,
+    level: Arc<InputLevel>,
+    selection: Vec<u16>,
+}
+
+impl CaptureSink {
+    fn push(&mut self, data: &[f32]) {
+        {
+            let mut buffer = self.samples.lock().expect("audio sample buffer poisoned");
+            if let Some(max) = self.max_samples {
+                let room = max.saturating_sub(buffer.len());
+                if room < data.len() {
+                    // Hard cap: keep what fits, then signal the UI to stop
+                    buffer.push(&data[..room / self.channels as usize * self.channels as usize]);
+                    self.limit_reached.store(true, Ordering::Relaxed);
+                    return;
+                }
+            }
+            buffer.push(data);
+        }
+
+        // Meter and auto-stop follow the selected channels only
+        let mixed = (!self.selection.is_empty())
+            .then(|| mix_channels(data, self.channels, &self.selection));
+        self.level.update(mixed.as_deref().unwrap_or(data));
+
+        if let Some(detector) = self.silence.as_mut() {
+            let mono = mixed.unwrap_or_else(|| downmix(data, self.channels));
+            if detector.feed(&mono) {
+                self.silence_reached.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Ring buffer holding the last moments of audio while the mic is armed,
+/// so the start of the next recording isn't clipped by device start-up.
+pub struct PreRollBuffer {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl PreRollBuffer {
+    /// Keep at most `frames` interleaved frames of `channels` samples each.
+    pub fn new(channels: u16, frames: usize) -> Self {
+        let capacity = frames * channels.max(1) as usize;
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Append whole frames, dropping the oldest ones past capacity.
+    pub fn push(&mut self, data: &[f32]) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.samples.extend(data);
+        let excess = self.samples.len().saturating_sub(self.capacity);
+        self.samples.drain(..excess);
+    }
+
+    /// Take the buffered audio, oldest first, leaving the buffer empty.
+    pub fn take(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+}
+
+/// Routes a stream's samples: into the capture sink while recording,
+/// otherwise into the pre-roll ring (a no-op unless the mic is armed).
+struct StreamFeed {
+    sink: Arc<Mutex<Option<CaptureSink>>>,
+    pre_roll: Arc<Mutex<PreRollBuffer>>,
+}
+
+impl StreamFeed {
+    fn push(&self, data: &[f32]) {
+        let mut sink = self.sink.lock().expect("capture sink poisoned");
+        match sink.as_mut() {
+            Some(sink) => sink.push(data),
+            None => self
+                .pre_roll
+                .lock()
+                .expect("pre-roll buffer poisoned")
+                .push(data),
+        }
+    }
+}
+
+/// A sample type capture devices deliver, convertible to f32 in [-1, 1].
+///
+/// Integers scale by their maximum like the WAV conversions elsewhere, so
+/// `i16` audio round-trips exactly; the most negative value clamps to -1.
+/// Unsigned formats are offset binary around the midpoint.
+pub trait CaptureSample: Copy {
+    fn to_f32(self) -> f32;
+}
+
+impl CaptureSample for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+impl CaptureSample for f64 {
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
+
+macro_rules! signed_capture_sample {
+    ($($t:ty),*) => {$(
+        impl CaptureSample for $t {
+            fn to_f32(self) -> f32 {
+                (self as f64 / <$t>::MAX as f64).max(-1.0) as f32
+            }
+        }
+    )*};
+}
+
+// Flipping the top bit of an offset-binary sample gives its signed value
+macro_rules! unsigned_capture_sample {
+    ($($t:ty => $signed:ty),*) => {$(
+        impl CaptureSample for $t {
+            fn to_f32(self) -> f32 {
+                ((self ^ (1 << (<$t>::BITS - 1))) as $signed).to_f32()
+            }
+        }
+    )*};
+}
+
+signed_capture_sample!(i8, i16, i32, i64);
+unsigned_capture_sample!(u8 => i8, u16 => i16, u32 => i32, u64 => i64);
+
+/// Convert a block of device samples to f32.
+pub fn samples_to_f32<T: CaptureSample>(data: &[T]) -> Vec<f32> {
+    data.iter().map(|&s| s.to_f32()).collect()
+}
+
+/// Average interleaved channels into mono.
+pub(crate) fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    if channels > 1 {
+        samples
+            .chunks(channels as usize)
+            .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
+            .collect()
+    } else {
+        samples.to_vec()
+    }
+}
+
+/// Mix interleaved audio to mono from the selected (0-based) channels only.
+/// An empty selection, or one naming no channel the device has, averages all
+/// channels like [`downmix`].
+pub fn mix_channels(samples: &[f32], channels: u16, selection: &[u16]) -> Vec<f32> {
+    let picked: Vec<usize> = selection
+        .iter()
+        .filter(|&&c| c < channels)
+        .map(|&c| c as usize)
+        .collect();
+    if picked.is_empty() || picked.len() == channels as usize {
+        return downmix(samples, channels);
+    }
+    samples
+        .chunks_exact(channels as usize)
+        .map(|frame| picked.iter().map(|&c| frame[c]).sum::<f32>() / picked.len() as f32)
+        .collect()
+}
+
+/// Parse a channel selection such as `"1,3"` (1-based, as shown to users)
+/// into 0-based indices. Empty or `"all"` selects every channel.
+pub fn parse_channel_selection(text: &str) -> Result<Vec<u16>, String> {
+    let text = text.trim();
+    if text.is_empty() || text.eq_ignore_ascii_case("all") {
+        return Ok(Vec::new());
+    }
+    let mut selection = Vec::new();
+    for part in text.split(',') {
+        let channel = part
+            .trim()
+            .parse::<u16>()
+            .ok()
+            .filter(|&c| (1..=64).contains(&c))
+            .ok_or_else(|| format!("Invalid channel: {:?}", part.trim()))?;
+        if !selection.contains(&(channel - 1)) {
+            selection.push(channel - 1);
+        }
+    }
+    selection.sort_unstable();
+    Ok(selection)
+}
+
+/// Format a selection back to its 1-based settings form (`""` = all).
+pub fn format_channel_selection(selection: &[u16]) -> String {
+    selection
+        .iter()
+        .map(|c| (c + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Copy the most recent `max_secs` of a shared interleaved buffer as mono.
+///
+/// Used to feed growing windows of an in-progress recording to the local
+/// model without stopping the stream. Only the in-memory part is read, which
+/// covers at least [`KEEP_IN_MEMORY_SECS`] for all but very wide devices.
+pub fn mono_tail(
+    buffer: &Mutex<CaptureBuffer>,
+    channels: u16,
+    selection: &[u16],
+    sample_rate: u32,
+    max_secs: u32,
+) -> Vec<f32> {
+    let Ok(buffer) = buffer.lock() else {
+        return Vec::new();
+    };
+    let frame = channels.max(1) as usize;
+    let samples = buffer.recent_tail(sample_rate as usize * max_secs as usize * frame);
+    drop(buffer);
+    mix_channels(&samples, channels, selection)
+}
+
+/// Read a WAV returned by [`Recorder::stop`] into memory, from the start.
+pub fn read_wav(file: &mut std::fs::File) -> Result<Vec<u8>, String> {
+    file.seek(std::io::SeekFrom::Start(0))
+        .map_err(|e| format!("WAV seek error: {e}"))?;
+    let mut wav = Vec::new();
+    file.read_to_end(&mut wav)
+        .map_err(|e| format!("WAV read error: {e}"))?;
+    Ok(wav)
+}
+
+/// Write WAV bytes to an anonymous temp file, the form [`Recorder::stop`]
+/// returns, rewound to the start.
+pub fn wav_file(wav: &[u8]) -> Result<std::fs::File, String> {
+    let mut file = tempfile::tempfile().map_err(|e| format!("Failed to create WAV file: {e}"))?;
+    file.write_all(wav)
+        .map_err(|e| format!("WAV write error: {e}"))?;
+    file.seek(std::io::SeekFrom::Start(0))
+        .map_err(|e| format!("WAV seek error: {e}"))?;
+    Ok(file)
+}
+
+/// Captures audio from an [`AudioSource`] (the selected or default input
+/// device unless configured otherwise) into an in-memory buffer.
 pub struct Recorder {
-    samples: Arc<Mutex<Vec<f32>>>,
-    stream: Option<cpal::Stream>,
+    samples: Arc<Mutex<CaptureBuffer>>,
+    source: Box<dyn AudioSource>,
+    stream: Option<Box<dyn SourceStream>>,
     sample_rate: u32,
     channels: u16,
+    max_duration_secs: Option<u32>,
+    limit_reached: Arc<AtomicBool>,
+    auto_stop_ms: Option<u32>,
+    silence_reached: Arc<AtomicBool>,
+    level: Arc<InputLevel>,
+    paused: bool,
+    sink: Arc<Mutex<Option<CaptureSink>>>,
+    pre_roll: Arc<Mutex<PreRollBuffer>>,
+    pre_roll_ms: Option<u32>,
+    selection: Vec<u16>,
 }
 
 impl Recorder {
-    pub fn new() -> Self {
-        let (sample_rate, channels) = Self::probe_input().unwrap_or((44100, 1));
+    pub fn new(device_name: Option<String>) -> Self {
+        Self::with_source(Box::new(CpalSource::new(device_name)))
+    }
+
+    pub fn with_source(source: Box<dyn AudioSource>) -> Self {
+        let (sample_rate, channels) = source.probe().unwrap_or((44100, 1));
         Self {
-            samples: Arc::new(Mutex::new(Vec::new())),
+            samples: Arc::new(Mutex::new(CaptureBuffer::new(channels, 0, 0))),
+            source,
             stream: None,
             sample_rate,
             channels,
+            max_duration_secs: None,
+            limit_reached: Arc::new(AtomicBool::new(false)),
+            auto_stop_ms: None,
+            silence_reached: Arc::new(AtomicBool::new(false)),
+            level: Arc::new(InputLevel::default()),
+            paused: false,
+            sink: Arc::new(Mutex::new(None)),
+            pre_roll: Arc::new(Mutex::new(PreRollBuffer::new(channels, 0))),
+            pre_roll_ms: None,
+            selection: Vec::new(),
         }
     }
 
-    /// Check whether an input device is available right now.
-    pub fn input_available() -> bool {
-        Self::probe_input().is_some()
+    /// Cap recordings at `secs` seconds (`None` = unlimited). Takes effect on the next `start`.
+    pub fn set_max_duration(&mut self, secs: Option<u32>) {
+        self.max_duration_secs = secs;
     }
 
-    fn probe_input() -> Option<(u32, u16)> {
-        let host = cpal::default_host();
-        let device = host.default_input_device()?;
-        let config = device.default_input_config().ok()?;
-        Some((config.sample_rate().0, config.channels()))
+    pub fn max_duration(&self) -> Option<u32> {
+        self.max_duration_secs
     }
 
-    pub fn start(&mut self) -> Result<(), String> {
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or("No input device available")?;
+    /// Whether the current recording has hit its maximum length.
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached.load(Ordering::Relaxed)
+    }
 
-        let config = device
-            .default_input_config()
-            .map_err(|e| format!("No input config: {e}"))?;
+    /// Length of the current recording in seconds.
+    pub fn elapsed_secs(&self) -> f32 {
+        let len = self.samples.lock().map(|b| b.len()).unwrap_or(0);
+        len as f32 / (self.sample_rate as f32 * self.channels.max(1) as f32)
+    }
 
-        let samples = Arc::clone(&self.samples);
-        samples
-            .lock()
-            .expect("audio sample buffer poisoned")
-            .clear();
-
-        let err_fn = |err| eprintln!("Audio stream error: {err}");
-
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => {
-                let samples = Arc::clone(&samples);
-                device
-                    .build_input_stream(
-                        &config.into(),
-                        move |data: &[f32], _: &_| {
-                            samples
-                                .lock()
-                                .expect("audio sample buffer poisoned")
-                                .extend_from_slice(data);
-                        },
-                        err_fn,
-                        None,
-                    )
-                    .map_err(|e| format!("Failed to build stream: {e}"))?
+    /// Enable auto-stop after `silence_ms` of trailing silence. Takes effect on the next `start`.
+    pub fn set_auto_stop(&mut self, silence_ms: Option<u32>) {
+        self.auto_stop_ms = silence_ms;
+    }
+
+    pub fn auto_stop_enabled(&self) -> bool {
+        self.auto_stop_ms.is_some()
+    }
+
+    /// Live input level of the current recording.
+    pub fn level(&self) -> (f32, f32) {
+        self.level.get()
+    }
+
+    /// Whether the current recording has hit its trailing-silence limit.
+    pub fn silence_reached(&self) -> bool {
+        self.silence_reached.load(Ordering::Relaxed)
+    }
+
+    /// Select the input device by name. `None` means the system default.
+    pub fn set_device(&mut self, device_name: Option<String>) {
+        self.source.set_device(device_name);
+        if let Some((sample_rate, channels)) = self.source.probe() {
+            self.sample_rate = sample_rate;
+            self.channels = channels;
+        }
+
+        // An armed idle stream follows the new device right away
+        if self.is_armed() {
+            self.stream = None;
+            if let Err(e) = self.open_armed_stream() {
+                eprintln!("Failed to re-arm microphone: {e}");
             }
-            cpal::SampleFormat::I16 => {
-                let samples = Arc::clone(&samples);
-                device
-                    .build_input_stream(
-                        &config.into(),
-                        move |data: &[i16], _: &_| {
-                            let floats: Vec<f32> =
-                                data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
-                            samples
-                                .lock()
-                                .expect("audio sample buffer poisoned")
-                                .extend_from_slice(&floats);
-                        },
-                        err_fn,
-                        None,
-                    )
-                    .map_err(|e| format!("Failed to build stream: {e}"))?
+        }
+    }
+
+    /// Record only these (0-based) input channels, mixed to mono. Empty
+    /// averages all channels. Takes effect on the next `start`.
+    pub fn set_channel_selection(&mut self, selection: Vec<u16>) {
+        self.selection = selection;
+    }
+
+    pub fn channel_selection(&self) -> &[u16] {
+        &self.selection
+    }
+
+    /// Check whether an input device is available right now.
+    pub fn input_available(&self) -> bool {
+        self.source.probe().is_some()
+    }
+
+    /// Error that broke the input stream during this recording, if any.
+    pub fn stream_error(&self) -> Option<String> {
+        self.stream.as_ref().and_then(|s| s.error())
+    }
+
+    /// The input failed mid-recording: release it, keeping what was captured
+    /// for `stop`, and move to the next available input for later recordings.
+    /// Returns the name of that input, if there is one.
+    pub fn fail_over(&mut self) -> Option<String> {
+        self.stream = None;
+        self.source.fail_over()
+    }
+
+    /// Whether a finite source (file, stdin) has run out during this recording.
+    pub fn input_ended(&self) -> bool {
+        self.stream.as_ref().is_some_and(|s| s.ended())
+    }
+
+    pub fn start(&mut self) -> Result<(), String> {
+        // Armed: the stream is already running, so capture starts instantly
+        // and the pre-roll becomes the beginning of the recording
+        if self.is_armed() {
+            if self.stream_error().is_none() {
+                self.begin_capture(true);
+                return Ok(());
             }
-            cpal::SampleFormat::U16 => {
-                let samples = Arc::clone(&samples);
-                device
-                    .build_input_stream(
-                        &config.into(),
-                        move |data: &[u16], _: &_| {
-                            let floats: Vec<f32> = data
-                                .iter()
-                                .map(|&s| (s as f32 / u16::MAX as f32) * 2.0 - 1.0)
-                                .collect();
-                            samples
-                                .lock()
-                                .expect("audio sample buffer poisoned")
-                                .extend_from_slice(&floats);
-                        },
-                        err_fn,
-                        None,
-                    )
-                    .map_err(|e| format!("Failed to build stream: {e}"))?
+            // The armed input broke while idle; open a fresh stream below
+            self.stream = None;
+        }
+
+        let stream = self.open_stream()?;
+
+        // The device may have changed since the last probe (fallback, replug)
+        (self.sample_rate, self.channels) = stream.format();
+
+        self.begin_capture(false);
+        if let Err(e) = stream.play() {
+            self.sink.lock().expect("capture sink poisoned").take();
+            return Err(e);
+        }
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    /// Reset the capture buffer and route the stream into it.
+    fn begin_capture(&mut self, with_pre_roll: bool) {
+        let frame_len = self.channels as usize;
+        let mut buffer = CaptureBuffer::new(
+            self.channels,
+            MEMORY_CAP_BYTES / std::mem::size_of::<f32>(),
+            KEEP_IN_MEMORY_SECS as usize * self.sample_rate as usize * frame_len,
+        );
+        self.limit_reached.store(false, Ordering::Relaxed);
+        self.silence_reached.store(false, Ordering::Relaxed);
+        self.level.reset();
+        self.paused = false;
+
+        // Holding the sink lock keeps the callback out until the switch is done,
+        // so no samples fall between the pre-roll and the recording
+        let mut sink = self.sink.lock().expect("capture sink poisoned");
+        if with_pre_roll {
+            let pre = self
+                .pre_roll
+                .lock()
+                .expect("pre-roll buffer poisoned")
+                .take();
+            dbg_log!("[AUDIO] prepending {} pre-roll samples", pre.len());
+            buffer.push(&pre);
+        }
+        *self.samples.lock().expect("audio sample buffer poisoned") = buffer;
+        *sink = Some(CaptureSink {
+            samples: Arc::clone(&self.samples),
+            channels: self.channels,
+            max_samples: self
+                .max_duration_secs
+                .map(|secs| secs as usize * self.sample_rate as usize * frame_len),
+            limit_reached: Arc::clone(&self.limit_reached),
+            silence: self
+                .auto_stop_ms
+                .map(|ms| SilenceDetector::new(self.sample_rate, VAD_RMS_THRESHOLD, ms)),
+            silence_reached: Arc::clone(&self.silence_reached),
+            level: Arc::clone(&self.level),
+            selection: self.selection.clone(),
+        });
+    }
+
+    /// Keep the microphone open while idle, holding the last `ms` of audio to
+    /// prepend to the next recording. `None` closes it again.
+    pub fn set_pre_roll(&mut self, ms: Option<u32>) -> Result<(), String> {
+        self.pre_roll_ms = ms;
+        if self.is_capturing() {
+            // Applied when the current recording stops
+            return Ok(());
+        }
+        self.stream = None;
+        match ms {
+            Some(_) => self.open_armed_stream(),
+            None => {
+                *self.pre_roll.lock().expect("pre-roll buffer poisoned") =
+                    PreRollBuffer::new(self.channels, 0);
+                Ok(())
             }
-            fmt => return Err(format!("Unsupported sample format: {fmt:?}")),
-        };
+        }
+    }
+
+    /// Whether the microphone is open while idle for pre-roll.
+    pub fn is_armed(&self) -> bool {
+        self.pre_roll_ms.is_some() && self.stream.is_some() && !self.is_capturing()
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.sink.lock().map(|s| s.is_some()).unwrap_or(false)
+    }
 
-        stream.play().map_err(|e| format!("Failed to play: {e}"))?;
+    fn open_armed_stream(&mut self) -> Result<(), String> {
+        let ms = self.pre_roll_ms.unwrap_or(0);
+        let stream = self.open_stream()?;
+        (self.sample_rate, self.channels) = stream.format();
+
+        let frames = self.sample_rate as usize * ms as usize / 1000;
+        *self.pre_roll.lock().expect("pre-roll buffer poisoned") =
+            PreRollBuffer::new(self.channels, frames);
+        stream.play()?;
         self.stream = Some(stream);
+        dbg_log!("[AUDIO] microphone armed with {ms} ms pre-roll");
         Ok(())
     }
 
-    pub fn stop(&mut self) -> Result<Vec<u8>, String> {
-        // Drop the stream to stop recording
-        self.stream.take();
+    /// Suspend capture, keeping what has been recorded so far.
+    pub fn pause(&mut self) {
+        if self.paused {
+            return;
+        }
+        if let Some(stream) = self.stream.as_ref()
+            && let Err(e) = stream.pause()
+        {
+            // Not every backend can pause; releasing the device works everywhere
+            dbg_log!("[AUDIO] stream pause unsupported ({e}), closing stream");
+            self.stream = None;
+        }
+        self.level.reset();
+        self.paused = true;
+    }
 
-        let samples = self
+    /// Continue a paused recording into the same buffer.
+    pub fn resume(&mut self) -> Result<(), String> {
+        if !self.paused {
+            return Ok(());
+        }
+        if let Some(stream) = self.stream.as_ref() {
+            stream.play()?;
+        } else {
+            let stream = self.open_stream()?;
+            // The buffer is interleaved at the original format; it can't change mid-recording
+            if stream.format() != (self.sample_rate, self.channels) {
+                return Err("Input device changed while paused".into());
+            }
+            stream.play()?;
+            self.stream = Some(stream);
+        }
+        self.paused = false;
+        Ok(())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Open the source with its samples routed to the capture sink, or the
+    /// pre-roll when idle. The stream is not playing yet.
+    fn open_stream(&self) -> Result<Box<dyn SourceStream>, String> {
+        let feed = StreamFeed {
+            sink: Arc::clone(&self.sink),
+            pre_roll: Arc::clone(&self.pre_roll),
+        };
+        self.source
+            .open(Box::new(move |data: &[f32]| feed.push(data)))
+    }
+
+    /// End the recording and return it as a mono 16-bit WAV in an anonymous
+    /// temp file, rewound to the start. The WAV is written straight from the
+    /// capture buffer and its spill file, so a long recording is never held
+    /// in memory here; see [`read_wav`].
+    pub fn stop(&mut self) -> Result<std::fs::File, String> {
+        // Stop feeding the recording. An armed stream keeps running for the
+        // next pre-roll; otherwise drop it to release the microphone.
+        self.sink.lock().expect("capture sink poisoned").take();
+        if self.pre_roll_ms.is_none() {
+            self.stream.take();
+        } else if self.paused
+            && let Some(stream) = self.stream.as_ref()
+            && let Err(e) = stream.play()
+        {
+            eprintln!("Failed to re-arm microphone: {e}");
+            self.stream = None;
+        }
+        self.paused = false;
+
+        let mut samples = self
             .samples
             .lock()
             .map_err(|_| "Audio buffer lock poisoned".to_string())?;
@@ -126,18 +1012,10 @@ impl Recorder {
             return Err("No audio recorded".into());
         }
 
-        // Convert to mono if multi-channel
-        let mono: Vec<f32> = if self.channels > 1 {
-            samples
-                .chunks(self.channels as usize)
-                .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
-                .collect()
-        } else {
-            samples.clone()
-        };
-
         // Encode as WAV
-        let mut buf = Cursor::new(Vec::new());
+        let mut file =
+            tempfile::tempfile().map_err(|e| format!("Failed to create WAV file: {e}"))?;
+        let mut buf = std::io::BufWriter::new(&mut file);
         let spec = hound::WavSpec {
             channels: 1,
             sample_rate: self.sample_rate,
@@ -147,20 +1025,51 @@ impl Recorder {
         let mut writer =
             hound::WavWriter::new(&mut buf, spec).map_err(|e| format!("WAV write error: {e}"))?;
 
-        for &sample in &mono {
-            let s = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
-            writer
-                .write_sample(s)
-                .map_err(|e| format!("WAV sample error: {e}"))?;
-        }
+        // Convert to mono chunk by chunk, so spilled recordings never load whole
+        let channels = self.channels;
+        let selection = &self.selection;
+        samples.for_each_chunk(|chunk| {
+            for sample in mix_channels(chunk, channels, selection) {
+                let s = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+                writer
+                    .write_sample(s)
+                    .map_err(|e| format!("WAV sample error: {e}"))?;
+            }
+            Ok(())
+        })?;
         writer
             .finalize()
             .map_err(|e| format!("WAV finalize error: {e}"))?;
+        buf.flush().map_err(|e| format!("WAV write error: {e}"))?;
+        drop(buf);
+
+        // Free the buffer (and its spill file) now that the WAV holds the audio
+        *samples = CaptureBuffer::new(channels, 0, 0);
+        drop(samples);
+
+        // Pre-roll stays on but its stream was lost (e.g. pause had to close it)
+        if self.pre_roll_ms.is_some()
+            && self.stream.is_none()
+            && let Err(e) = self.open_armed_stream()
+        {
+            eprintln!("Failed to re-arm microphone: {e}");
+        }
 
-        Ok(buf.into_inner())
+        file.seek(std::io::SeekFrom::Start(0))
+            .map_err(|e| format!("WAV seek error: {e}"))?;
+        Ok(file)
     }
 
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
     }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Shared handle to the live sample buffer (interleaved, device rate).
+    pub fn sample_buffer(&self) -> Arc<Mutex<CaptureBuffer>> {
+        Arc::clone(&self.samples)
+    }
 }
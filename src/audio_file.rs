@@ -0,0 +1,154 @@
+//! Decoding of existing audio files (voice memos, meeting exports) so they can
+//! go through the same transcription backends as live recordings.
+
+use std::fs::File;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, CODEC_TYPE_OPUS, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// File extensions accepted for transcription. Opus is left out: symphonia
+/// has no decoder for it.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["wav", "flac", "mp3", "ogg", "oga", "m4a"];
+
+/// Sample rate of WAVs produced by [`file_to_wav`] (what whisper expects).
+pub const FILE_SAMPLE_RATE: u32 = 16000;
+
+/// Whether the path has one of the [`SUPPORTED_EXTENSIONS`].
+pub fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Split a `transcribe-file` request, `<file>` or `<file>|<subtitles>`, into
+/// the audio file and where to write its subtitles. A request that names an
+/// existing file as a whole is just that file, so a `|` in a name still works.
+pub fn parse_file_request(param: &str) -> (PathBuf, Option<PathBuf>) {
+    if !Path::new(param).exists()
+        && let Some((file, subtitles)) = param.rsplit_once('|')
+        && !subtitles.is_empty()
+    {
+        return (PathBuf::from(file), Some(PathBuf::from(subtitles)));
+    }
+    (PathBuf::from(param), None)
+}
+
+/// Decode the first audio track of a file to mono f32 samples.
+/// Returns the samples and their sample rate.
+pub fn decode_file(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open {}: {e}", path.display()))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Unsupported audio file: {e}"))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("No audio track found")?;
+    if track.codec_params.codec == CODEC_TYPE_OPUS {
+        // .ogg files may carry Opus too
+        return Err("Opus audio is not supported, convert it to FLAC, MP3 or Ogg Vorbis".into());
+    }
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported codec: {e}"))?;
+
+    let mut samples = Vec::new();
+    let mut buf: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(p) => p,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(format!("Read error: {e}")),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(d) => d,
+            // Corrupt frame: skip it and keep going
+            Err(SymphoniaError::DecodeError(e)) => {
+                dbg_log!("[FILE] skipping undecodable packet: {e}");
+                continue;
+            }
+            Err(e) => return Err(format!("Decode error: {e}")),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        let needed = decoded.capacity() as u64;
+        if buf.as_ref().is_none_or(|b| (b.capacity() as u64) < needed) {
+            buf = Some(SampleBuffer::new(needed, spec));
+        }
+        if let Some(b) = buf.as_mut() {
+            b.copy_interleaved_ref(decoded);
+            samples.extend(crate::audio::downmix(
+                b.samples(),
+                spec.channels.count() as u16,
+            ));
+        }
+    }
+
+    if samples.is_empty() || sample_rate == 0 {
+        return Err("No audio in file".into());
+    }
+    Ok((samples, sample_rate))
+}
+
+/// Decode an audio file and convert it to a 16 kHz mono 16-bit WAV,
+/// ready for either transcription backend.
+pub fn file_to_wav(path: &Path) -> Result<Vec<u8>, String> {
+    let (samples, sample_rate) = decode_file(path)?;
+    let samples = if sample_rate == FILE_SAMPLE_RATE {
+        samples
+    } else {
+        crate::local_stt::resample(&samples, sample_rate, FILE_SAMPLE_RATE)?
+    };
+
+    let mut buf = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: FILE_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer =
+        hound::WavWriter::new(&mut buf, spec).map_err(|e| format!("WAV write error: {e}"))?;
+    for &sample in &samples {
+        let s = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        writer
+            .write_sample(s)
+            .map_err(|e| format!("WAV sample error: {e}"))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("WAV finalize error: {e}"))?;
+    Ok(buf.into_inner())
+}
@@ -0,0 +1,734 @@
+//! Where recorded samples come from.
+//!
+//! [`Recorder`](crate::audio::Recorder) is built on the [`AudioSource`] trait,
+//! so the record → transcribe flow can run from a WAV/raw file, piped PCM or a
+//! generated tone instead of a sound card (headless CI, SSH sessions).
+
+use std::cell::RefCell;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+use crate::audio::{CaptureSample, samples_to_f32};
+use crate::config::{AudioSourceSpec, RawPcmFormat};
+
+/// Receives interleaved f32 samples as they are captured.
+pub type SampleCallback = Box<dyn FnMut(&[f32]) + Send + 'static>;
+
+/// A producer of interleaved f32 audio.
+pub trait AudioSource {
+    /// Sample rate and channel count a new stream would deliver, or `None`
+    /// when the source is unavailable right now.
+    fn probe(&self) -> Option<(u32, u16)>;
+
+    /// Open a stream that delivers samples to `on_data` once played.
+    fn open(&self, on_data: SampleCallback) -> Result<Box<dyn SourceStream>, String>;
+
+    /// Select an input device by name. Sources without devices ignore it.
+    fn set_device(&mut self, _name: Option<String>) {}
+
+    /// The input failed mid-recording: move to the next available one for
+    /// later streams. Returns its name, or `None` when there is nothing to
+    /// switch to.
+    fn fail_over(&mut self) -> Option<String> {
+        None
+    }
+}
+
+/// An open source stream. Dropping it stops delivery.
+pub trait SourceStream {
+    /// Sample rate and channel count delivered to the callback.
+    fn format(&self) -> (u32, u16);
+
+    fn play(&self) -> Result<(), String>;
+
+    fn pause(&self) -> Result<(), String>;
+
+    /// Whether a finite source has delivered all of its audio.
+    fn ended(&self) -> bool {
+        false
+    }
+
+    /// Error that broke the stream (device unplugged, backend failure).
+    fn error(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Build the source selected in config (`AUDIO_SOURCE` / `--audio-source`).
+pub fn from_spec(
+    spec: &AudioSourceSpec,
+    raw: RawPcmFormat,
+    device_name: Option<String>,
+) -> Box<dyn AudioSource> {
+    match spec {
+        AudioSourceSpec::Device => Box::new(CpalSource::new(device_name)),
+        AudioSourceSpec::File(path) => Box::new(FileSource::new(path.clone(), raw)),
+        AudioSourceSpec::Stdin => Box::new(StdinSource::new(raw)),
+        AudioSourceSpec::Tone(freq) => Box::new(ToneSource::new(*freq)),
+    }
+}
+
+// ── cpal (sound card) ───────────────────────────────────────────────────────
+
+/// Find an input device by name, falling back to the host default when the
+/// name is unset or the device is no longer present.
+fn find_input_device(name: Option<&str>) -> Option<cpal::Device> {
+    let host = crate::audio::host();
+    if let Some(name) = name {
+        let found = host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().ok().as_deref() == Some(name)));
+        if found.is_some() {
+            return found;
+        }
+        dbg_log!("[AUDIO] input device {name:?} not found, using default");
+    }
+    host.default_input_device()
+}
+
+/// Live capture from the selected (or default) input device.
+pub struct CpalSource {
+    device_name: Option<String>,
+    /// Device taken over after the selected one (or the default, with none
+    /// selected) failed, used while that one is missing.
+    fallback: Option<String>,
+    /// Name of the device the last stream was opened on.
+    opened: RefCell<Option<String>>,
+    /// Input device names and when they were listed; enumerating is slow on
+    /// some backends, and the list is checked every time a stream opens.
+    devices: RefCell<Option<(Instant, Vec<String>)>>,
+}
+
+/// How long a listing of input devices is reused.
+const DEVICE_LIST_TTL: Duration = Duration::from_secs(2);
+
+impl CpalSource {
+    pub fn new(device_name: Option<String>) -> Self {
+        Self {
+            device_name,
+            fallback: None,
+            opened: RefCell::new(None),
+            devices: RefCell::new(None),
+        }
+    }
+
+    fn device_names(&self) -> Vec<String> {
+        let mut cache = self.devices.borrow_mut();
+        if let Some((listed, names)) = cache.as_ref()
+            && listed.elapsed() < DEVICE_LIST_TTL
+        {
+            return names.clone();
+        }
+        let names = crate::audio::input_devices();
+        *cache = Some((Instant::now(), names.clone()));
+        names
+    }
+
+    /// The selected device, or with none selected the current default, while
+    /// it is present; otherwise the one taken over after it failed.
+    fn device(&self) -> Option<cpal::Device> {
+        if let Some(fallback) = self.fallback.as_deref() {
+            let wanted = self.device_name.clone().or_else(|| {
+                crate::audio::host()
+                    .default_input_device()
+                    .and_then(|d| d.name().ok())
+            });
+            if !wanted.is_some_and(|name| self.device_names().contains(&name)) {
+                return find_input_device(Some(fallback));
+            }
+        }
+        find_input_device(self.device_name.as_deref())
+    }
+}
+
+impl AudioSource for CpalSource {
+    fn probe(&self) -> Option<(u32, u16)> {
+        let device = self.device()?;
+        let default = device.default_input_config().ok()?;
+        let config = capture_config(&device, &default);
+        Some((config.sample_rate().0, config.channels()))
+    }
+
+    fn open(&self, mut on_data: SampleCallback) -> Result<Box<dyn SourceStream>, String> {
+        let device = self.device().ok_or("No input device available")?;
+        let default = device
+            .default_input_config()
+            .map_err(|e| format!("No input config: {e}"))?;
+        let config = capture_config(&device, &default);
+        *self.opened.borrow_mut() = device.name().ok();
+
+        // Note when audio last arrived, so a device that drops without an
+        // error (some Bluetooth headsets) is still noticed
+        let last_data = Arc::new(Mutex::new(Instant::now()));
+        let on_data: Arc<Mutex<SampleCallback>> = {
+            let last_data = Arc::clone(&last_data);
+            Arc::new(Mutex::new(Box::new(move |data: &[f32]| {
+                if let Ok(mut last) = last_data.lock() {
+                    *last = Instant::now();
+                }
+                on_data(data);
+            })))
+        };
+
+        // Keep the first error for the UI; later ones are usually fallout
+        let error = Arc::new(Mutex::new(None));
+        let err_fn = || {
+            let error = Arc::clone(&error);
+            move |err: cpal::StreamError| {
+                eprintln!("Audio stream error: {err}");
+                if let Ok(mut slot) = error.lock() {
+                    slot.get_or_insert_with(|| err.to_string());
+                }
+            }
+        };
+
+        // A config picked from the supported ranges can still be refused by
+        // the backend; the default one is what always worked before
+        let (stream, config) =
+            match build_cpal_stream(&device, config.clone(), Arc::clone(&on_data), err_fn()) {
+                Ok(stream) => (stream, config),
+                Err(e) if config != default => {
+                    dbg_log!("[AUDIO] preferred capture config refused ({e}), using default");
+                    let stream = build_cpal_stream(&device, default.clone(), on_data, err_fn())?;
+                    (stream, default)
+                }
+                Err(e) => return Err(e),
+            };
+        dbg_log!(
+            "[AUDIO] capturing at {} Hz, {} ch, {:?}",
+            config.sample_rate().0,
+            config.channels(),
+            config.sample_format()
+        );
+
+        Ok(Box::new(CpalStream {
+            stream,
+            format: (config.sample_rate().0, config.channels()),
+            error,
+            last_data,
+            playing: AtomicBool::new(false),
+        }))
+    }
+
+    fn set_device(&mut self, name: Option<String>) {
+        self.device_name = name;
+        self.fallback = None;
+        self.devices.take();
+    }
+
+    fn fail_over(&mut self) -> Option<String> {
+        let lost = self.opened.borrow().clone();
+        let host = crate::audio::host();
+        let default = host.default_input_device().and_then(|d| d.name().ok());
+        // The list just changed; don't offer the vanished device from it
+        self.devices.take();
+        // Prefer the system default unless it is the input that just failed
+        let next = default
+            .into_iter()
+            .chain(self.device_names())
+            .find(|name| Some(name) != lost.as_ref())?;
+        dbg_log!("[AUDIO] input {lost:?} failed, switching to {next:?}");
+        self.fallback = Some(next.clone());
+        Some(next)
+    }
+}
+
+/// Sample formats the capture callbacks can convert to f32.
+fn is_convertible(format: cpal::SampleFormat) -> bool {
+    use cpal::SampleFormat::*;
+    matches!(
+        format,
+        I8 | I16 | I32 | I64 | U8 | U16 | U32 | U64 | F32 | F64
+    )
+}
+
+/// Capture config for a device: its own channel layout (so input channel
+/// selection keeps working) at 16 kHz, or the lowest integer multiple of it,
+/// so transcription skips the general resampler. Falls back to the default.
+fn capture_config(
+    device: &cpal::Device,
+    default: &cpal::SupportedStreamConfig,
+) -> cpal::SupportedStreamConfig {
+    let Ok(ranges) = device.supported_input_configs() else {
+        return default.clone();
+    };
+    let ranges: Vec<_> = ranges
+        .filter(|r| r.channels() == default.channels() && is_convertible(r.sample_format()))
+        .collect();
+    let rates: Vec<(u32, u32)> = ranges
+        .iter()
+        .map(|r| (r.min_sample_rate().0, r.max_sample_rate().0))
+        .collect();
+    let Some(rate) = pick_capture_rate(&rates, default.sample_rate().0) else {
+        return default.clone();
+    };
+    if rate == default.sample_rate().0 {
+        return default.clone();
+    }
+    // Keep the default sample format when it offers the rate too
+    ranges
+        .into_iter()
+        .filter(|r| (r.min_sample_rate().0..=r.max_sample_rate().0).contains(&rate))
+        .max_by_key(|r| r.sample_format() == default.sample_format())
+        .map(|r| r.with_sample_rate(cpal::SampleRate(rate)))
+        .unwrap_or_else(|| default.clone())
+}
+
+/// Open a cpal input stream converting the device's samples to f32.
+fn build_cpal_stream(
+    device: &cpal::Device,
+    config: cpal::SupportedStreamConfig,
+    on_data: Arc<Mutex<SampleCallback>>,
+    err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream, String> {
+    use cpal::SampleFormat;
+    let format = config.sample_format();
+    let config: cpal::StreamConfig = config.into();
+    let stream = match format {
+        // Already f32: hand the device buffer over without a copy
+        SampleFormat::F32 => device.build_input_stream(
+            &config,
+            move |data: &[f32], _: &_| {
+                if let Ok(mut on_data) = on_data.lock() {
+                    on_data(data);
+                }
+            },
+            err_fn,
+            None,
+        ),
+        SampleFormat::F64 => build_converting::<f64>(device, &config, on_data, err_fn),
+        SampleFormat::I8 => build_converting::<i8>(device, &config, on_data, err_fn),
+        SampleFormat::I16 => build_converting::<i16>(device, &config, on_data, err_fn),
+        SampleFormat::I32 => build_converting::<i32>(device, &config, on_data, err_fn),
+        SampleFormat::I64 => build_converting::<i64>(device, &config, on_data, err_fn),
+        SampleFormat::U8 => build_converting::<u8>(device, &config, on_data, err_fn),
+        SampleFormat::U16 => build_converting::<u16>(device, &config, on_data, err_fn),
+        SampleFormat::U32 => build_converting::<u32>(device, &config, on_data, err_fn),
+        SampleFormat::U64 => build_converting::<u64>(device, &config, on_data, err_fn),
+        fmt => return Err(format!("Unsupported sample format: {fmt:?}")),
+    };
+    stream.map_err(|e| format!("Failed to build stream: {e}"))
+}
+
+/// Input stream for a non-f32 format, converting each buffer before the callback.
+fn build_converting<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    on_data: Arc<Mutex<SampleCallback>>,
+    err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: cpal::SizedSample + CaptureSample,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &_| {
+            let floats = samples_to_f32(data);
+            if let Ok(mut on_data) = on_data.lock() {
+                on_data(&floats);
+            }
+        },
+        err_fn,
+        None,
+    )
+}
+
+/// How long a playing device may go without delivering audio before it
+/// counts as lost.
+const DEVICE_STALL_TIMEOUT: Duration = Duration::from_secs(3);
+
+struct CpalStream {
+    stream: cpal::Stream,
+    format: (u32, u16),
+    error: Arc<Mutex<Option<String>>>,
+    last_data: Arc<Mutex<Instant>>,
+    playing: AtomicBool,
+}
+
+impl SourceStream for CpalStream {
+    fn format(&self) -> (u32, u16) {
+        self.format
+    }
+
+    fn play(&self) -> Result<(), String> {
+        self.stream
+            .play()
+            .map_err(|e| format!("Failed to play: {e}"))?;
+        if let Ok(mut last) = self.last_data.lock() {
+            *last = Instant::now();
+        }
+        self.playing.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn pause(&self) -> Result<(), String> {
+        self.playing.store(false, Ordering::Relaxed);
+        self.stream
+            .pause()
+            .map_err(|e| format!("Failed to pause: {e}"))
+    }
+
+    fn error(&self) -> Option<String> {
+        if let Some(err) = self.error.lock().ok().and_then(|e| e.clone()) {
+            return Some(err);
+        }
+        let stalled = self.playing.load(Ordering::Relaxed)
+            && self
+                .last_data
+                .lock()
+                .is_ok_and(|last| last.elapsed() >= DEVICE_STALL_TIMEOUT);
+        stalled.then(|| "No audio from input device".to_string())
+    }
+}
+
+/// Rate transcription runs at; capture configs at this rate need no resampling.
+pub const PREFERRED_CAPTURE_RATE: u32 = 16000;
+
+/// Highest multiple of [`PREFERRED_CAPTURE_RATE`] considered (96 kHz).
+const MAX_RATE_MULTIPLE: u32 = 6;
+
+/// Choose a capture rate from a device's supported (min, max) rate ranges:
+/// 16 kHz if offered, else the lowest integer multiple of it, else `None`
+/// to keep the default. A multiple above the default rate is never chosen.
+pub fn pick_capture_rate(ranges: &[(u32, u32)], default_rate: u32) -> Option<u32> {
+    (1..=MAX_RATE_MULTIPLE)
+        .map(|k| PREFERRED_CAPTURE_RATE * k)
+        .filter(|&rate| rate == PREFERRED_CAPTURE_RATE || rate <= default_rate)
+        .find(|&rate| ranges.iter().any(|&(min, max)| (min..=max).contains(&rate)))
+}
+
+// ── Generated and file-backed sources ───────────────────────────────────────
+
+/// Audio delivered per callback by the generated and file sources.
+const CHUNK_MS: u32 = 20;
+
+/// Stream driven by a background thread that calls the sample callback every
+/// [`CHUNK_MS`], like a sound card would.
+struct ThreadStream {
+    format: (u32, u16),
+    playing: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    ended: Arc<AtomicBool>,
+}
+
+impl ThreadStream {
+    /// `fill` writes the next chunk into the buffer and returns how many
+    /// samples it wrote; 0 ends the stream.
+    fn spawn(
+        sample_rate: u32,
+        channels: u16,
+        mut fill: impl FnMut(&mut [f32]) -> usize + Send + 'static,
+        mut on_data: SampleCallback,
+    ) -> Self {
+        let playing = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let ended = Arc::new(AtomicBool::new(false));
+        let chunk_len = (sample_rate * CHUNK_MS / 1000).max(1) as usize * channels.max(1) as usize;
+
+        let (playing_t, stop_t, ended_t) =
+            (Arc::clone(&playing), Arc::clone(&stop), Arc::clone(&ended));
+        std::thread::spawn(move || {
+            let mut chunk = vec![0.0f32; chunk_len];
+            while !stop_t.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(CHUNK_MS as u64));
+                if !playing_t.load(Ordering::Relaxed) {
+                    continue;
+                }
+                let n = fill(&mut chunk);
+                if n == 0 {
+                    ended_t.store(true, Ordering::Relaxed);
+                    break;
+                }
+                on_data(&chunk[..n]);
+            }
+        });
+
+        Self {
+            format: (sample_rate, channels),
+            playing,
+            stop,
+            ended,
+        }
+    }
+}
+
+impl SourceStream for ThreadStream {
+    fn format(&self) -> (u32, u16) {
+        self.format
+    }
+
+    fn play(&self) -> Result<(), String> {
+        self.playing.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn pause(&self) -> Result<(), String> {
+        self.playing.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn ended(&self) -> bool {
+        self.ended.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for ThreadStream {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Sample rate of the generated test tone.
+pub const TONE_SAMPLE_RATE: u32 = 16000;
+
+/// Amplitude of the generated test tone (about -10 dBFS).
+const TONE_AMPLITUDE: f32 = 0.3;
+
+/// An endless mono sine tone.
+pub struct ToneSource {
+    freq: f32,
+}
+
+impl ToneSource {
+    pub fn new(freq: f32) -> Self {
+        Self { freq }
+    }
+}
+
+impl AudioSource for ToneSource {
+    fn probe(&self) -> Option<(u32, u16)> {
+        Some((TONE_SAMPLE_RATE, 1))
+    }
+
+    fn open(&self, on_data: SampleCallback) -> Result<Box<dyn SourceStream>, String> {
+        let step = std::f32::consts::TAU * self.freq / TONE_SAMPLE_RATE as f32;
+        let mut phase = 0.0f32;
+        let fill = move |chunk: &mut [f32]| {
+            for s in chunk.iter_mut() {
+                *s = TONE_AMPLITUDE * phase.sin();
+                phase = (phase + step) % std::f32::consts::TAU;
+            }
+            chunk.len()
+        };
+        Ok(Box::new(ThreadStream::spawn(
+            TONE_SAMPLE_RATE,
+            1,
+            fill,
+            on_data,
+        )))
+    }
+}
+
+/// A WAV file, or headerless PCM in the raw format, played back in real time.
+/// Every recording starts again from the beginning of the file.
+pub struct FileSource {
+    path: PathBuf,
+    raw: RawPcmFormat,
+}
+
+impl FileSource {
+    pub fn new(path: PathBuf, raw: RawPcmFormat) -> Self {
+        Self { path, raw }
+    }
+}
+
+impl AudioSource for FileSource {
+    fn probe(&self) -> Option<(u32, u16)> {
+        let mut header = [0u8; 4];
+        let mut file = std::fs::File::open(&self.path).ok()?;
+        if file.read_exact(&mut header).is_ok() && &header == b"RIFF" {
+            let reader = hound::WavReader::open(&self.path).ok()?;
+            let spec = reader.spec();
+            return Some((spec.sample_rate, spec.channels));
+        }
+        Some((self.raw.sample_rate, self.raw.channels))
+    }
+
+    fn open(&self, on_data: SampleCallback) -> Result<Box<dyn SourceStream>, String> {
+        let (samples, sample_rate, channels) = read_pcm_file(&self.path, self.raw)?;
+        let mut pos = 0;
+        let fill = move |chunk: &mut [f32]| {
+            let n = chunk.len().min(samples.len() - pos);
+            chunk[..n].copy_from_slice(&samples[pos..pos + n]);
+            pos += n;
+            n
+        };
+        Ok(Box::new(ThreadStream::spawn(
+            sample_rate,
+            channels,
+            fill,
+            on_data,
+        )))
+    }
+}
+
+/// Read a WAV file (any PCM/float layout) or headerless s16le PCM as
+/// interleaved f32. Returns the samples, sample rate and channel count.
+pub fn read_pcm_file(path: &Path, raw: RawPcmFormat) -> Result<(Vec<f32>, u32, u16), String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Cannot open {}: {e}", path.display()))?;
+    if !bytes.starts_with(b"RIFF") {
+        return Ok((decode_s16le(&bytes), raw.sample_rate, raw.channels));
+    }
+
+    let mut reader =
+        hound::WavReader::new(Cursor::new(bytes)).map_err(|e| format!("WAV parse error: {e}"))?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().map(|s| s.unwrap_or(0.0)).collect(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.unwrap_or(0) as f32 / scale)
+                .collect()
+        }
+    };
+    Ok((samples, spec.sample_rate, spec.channels))
+}
+
+/// Convert signed 16-bit little-endian bytes to f32 (a trailing odd byte is dropped).
+pub fn decode_s16le(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+        .collect()
+}
+
+// ── stdin ───────────────────────────────────────────────────────────────────
+
+/// Callback of the stream currently reading stdin, if any.
+type StdinTarget = Arc<Mutex<Option<SampleCallback>>>;
+
+/// stdin can only be read once, so a single reader thread lives for the whole
+/// process and hands data to whichever stream is playing. Audio arriving
+/// while nothing records is discarded, like a microphone nobody listens to.
+struct StdinReader {
+    target: StdinTarget,
+    ended: Arc<AtomicBool>,
+}
+
+fn stdin_reader(raw: RawPcmFormat) -> &'static StdinReader {
+    static READER: OnceLock<StdinReader> = OnceLock::new();
+    READER.get_or_init(|| {
+        let target: StdinTarget = Arc::new(Mutex::new(None));
+        let ended = Arc::new(AtomicBool::new(false));
+        let (target_t, ended_t) = (Arc::clone(&target), Arc::clone(&ended));
+        let frame_bytes = 2 * raw.channels.max(1) as usize;
+        std::thread::spawn(move || {
+            let mut stdin = std::io::stdin().lock();
+            let mut buf = vec![0u8; frame_bytes * (raw.sample_rate * CHUNK_MS / 1000) as usize];
+            let mut pending = Vec::new();
+            loop {
+                let n = match stdin.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => n,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        eprintln!("stdin audio read error: {e}");
+                        break;
+                    }
+                };
+                // Only deliver whole frames; keep a partial one for the next read
+                pending.extend_from_slice(&buf[..n]);
+                let whole = pending.len() / frame_bytes * frame_bytes;
+                let samples = decode_s16le(&pending[..whole]);
+                pending.drain(..whole);
+                if let Ok(mut target) = target_t.lock()
+                    && let Some(on_data) = target.as_mut()
+                {
+                    on_data(&samples);
+                }
+            }
+            dbg_log!("[AUDIO] end of stdin audio");
+            ended_t.store(true, Ordering::Relaxed);
+        });
+        StdinReader { target, ended }
+    })
+}
+
+/// Headerless PCM piped on stdin, e.g. `arecord -f S16_LE -r 16000 | whispercrabs --audio-source=stdin`.
+pub struct StdinSource {
+    raw: RawPcmFormat,
+}
+
+impl StdinSource {
+    pub fn new(raw: RawPcmFormat) -> Self {
+        Self { raw }
+    }
+}
+
+impl AudioSource for StdinSource {
+    fn probe(&self) -> Option<(u32, u16)> {
+        let ended = stdin_reader(self.raw).ended.load(Ordering::Relaxed);
+        (!ended).then_some((self.raw.sample_rate, self.raw.channels))
+    }
+
+    fn open(&self, on_data: SampleCallback) -> Result<Box<dyn SourceStream>, String> {
+        let reader = stdin_reader(self.raw);
+        if reader.ended.load(Ordering::Relaxed) {
+            return Err("stdin audio has ended".into());
+        }
+        Ok(Box::new(StdinStream {
+            format: (self.raw.sample_rate, self.raw.channels),
+            reader,
+            pending: Mutex::new(Some(on_data)),
+        }))
+    }
+}
+
+struct StdinStream {
+    format: (u32, u16),
+    reader: &'static StdinReader,
+    /// The callback while paused or not yet played; it sits in the reader otherwise.
+    pending: Mutex<Option<SampleCallback>>,
+}
+
+impl SourceStream for StdinStream {
+    fn format(&self) -> (u32, u16) {
+        self.format
+    }
+
+    fn play(&self) -> Result<(), String> {
+        let mut pending = self.pending.lock().map_err(|_| "stdin stream poisoned")?;
+        if let Some(on_data) = pending.take() {
+            *self
+                .reader
+                .target
+                .lock()
+                .map_err(|_| "stdin reader poisoned")? = Some(on_data);
+        }
+        Ok(())
+    }
+
+    fn pause(&self) -> Result<(), String> {
+        let mut pending = self.pending.lock().map_err(|_| "stdin stream poisoned")?;
+        if pending.is_none() {
+            *pending = self
+                .reader
+                .target
+                .lock()
+                .map_err(|_| "stdin reader poisoned")?
+                .take();
+        }
+        Ok(())
+    }
+
+    fn ended(&self) -> bool {
+        self.reader.ended.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for StdinStream {
+    fn drop(&mut self) {
+        let playing = self.pending.lock().map(|p| p.is_none()).unwrap_or(false);
+        if playing && let Ok(mut target) = self.reader.target.lock() {
+            target.take();
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Active transcription backend.
 #[derive(Clone, Copy, PartialEq)]
@@ -59,33 +59,200 @@ pub struct LocalModelPreset {
     pub label: &'static str,
     pub file_name: &'static str,
     pub size_label: &'static str,
+    /// Transcribes any of whisper's languages; the others are English-only.
+    pub multilingual: bool,
+    /// HuggingFace repository, when not ggerganov/whisper.cpp.
+    pub repo: Option<&'static str>,
+    /// SHA-256 of the file, or empty when not pinned yet (the download then
+    /// trusts the checksum the server publishes).
+    pub sha256: &'static str,
 }
 
-/// Available local whisper model sizes (Tiny through Medium).
+impl LocalModelPreset {
+    /// Download URL of the model file on `endpoint` (HuggingFace or a mirror).
+    pub fn url(&self, endpoint: &str) -> String {
+        match self.repo {
+            Some(repo) => huggingface_url(endpoint, repo, self.file_name),
+            None => model_url(endpoint, self.file_name),
+        }
+    }
+
+    /// The pinned SHA-256 of the file, if any.
+    pub fn pinned_sha256(&self) -> Option<&'static str> {
+        pinned(self.sha256)
+    }
+}
+
+/// A pinned checksum, with empty meaning none.
+fn pinned(sha256: &'static str) -> Option<&'static str> {
+    (!sha256.is_empty()).then_some(sha256)
+}
+
+/// Available local whisper models: English-only Tiny through Distil Large v3,
+/// then multilingual Base through Large v3 Turbo. The Q5/Q8 variants are
+/// quantized: smaller and faster on CPU, slightly less accurate.
 pub const LOCAL_MODEL_PRESETS: &[LocalModelPreset] = &[
     LocalModelPreset {
         id: "local-tiny",
         label: "Tiny",
         file_name: "ggml-tiny.en.bin",
         size_label: "~75 MB",
+        multilingual: false,
+        repo: None,
+        sha256: "",
     },
     LocalModelPreset {
         id: "local-base",
         label: "Base",
         file_name: "ggml-base.en.bin",
         size_label: "~142 MB",
+        multilingual: false,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-base-q5",
+        label: "Base Q5",
+        file_name: "ggml-base.en-q5_1.bin",
+        size_label: "~57 MB",
+        multilingual: false,
+        repo: None,
+        sha256: "",
     },
     LocalModelPreset {
         id: "local-small",
         label: "Small",
         file_name: "ggml-small.en.bin",
         size_label: "~466 MB",
+        multilingual: false,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-small-q5",
+        label: "Small Q5",
+        file_name: "ggml-small.en-q5_1.bin",
+        size_label: "~190 MB",
+        multilingual: false,
+        repo: None,
+        sha256: "",
     },
     LocalModelPreset {
         id: "local-medium",
         label: "Medium",
         file_name: "ggml-medium.en.bin",
         size_label: "~1.5 GB",
+        multilingual: false,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-medium-q5",
+        label: "Medium Q5",
+        file_name: "ggml-medium.en-q5_0.bin",
+        size_label: "~539 MB",
+        multilingual: false,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-distil-large-v3",
+        label: "Distil Large v3",
+        file_name: "ggml-distil-large-v3.bin",
+        size_label: "~1.5 GB",
+        multilingual: false,
+        repo: Some("distil-whisper/distil-large-v3-ggml"),
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-base-multi",
+        label: "Base",
+        file_name: "ggml-base.bin",
+        size_label: "~142 MB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-small-multi",
+        label: "Small",
+        file_name: "ggml-small.bin",
+        size_label: "~466 MB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-medium-multi",
+        label: "Medium",
+        file_name: "ggml-medium.bin",
+        size_label: "~1.5 GB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-medium-multi-q5",
+        label: "Medium Q5",
+        file_name: "ggml-medium-q5_0.bin",
+        size_label: "~539 MB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-medium-multi-q8",
+        label: "Medium Q8",
+        file_name: "ggml-medium-q8_0.bin",
+        size_label: "~823 MB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-large-v3",
+        label: "Large v3",
+        file_name: "ggml-large-v3.bin",
+        size_label: "~3.1 GB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-large-v3-q5",
+        label: "Large v3 Q5",
+        file_name: "ggml-large-v3-q5_0.bin",
+        size_label: "~1.1 GB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-large-v3-turbo",
+        label: "Large v3 Turbo",
+        file_name: "ggml-large-v3-turbo.bin",
+        size_label: "~1.6 GB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-large-v3-turbo-q5",
+        label: "Large v3 Turbo Q5",
+        file_name: "ggml-large-v3-turbo-q5_0.bin",
+        size_label: "~574 MB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
+    },
+    LocalModelPreset {
+        id: "local-large-v3-turbo-q8",
+        label: "Large v3 Turbo Q8",
+        file_name: "ggml-large-v3-turbo-q8_0.bin",
+        size_label: "~874 MB",
+        multilingual: true,
+        repo: None,
+        sha256: "",
     },
 ];
 
@@ -97,14 +264,280 @@ pub fn find_local_model(id: &str) -> Option<&'static LocalModelPreset> {
     LOCAL_MODEL_PRESETS.iter().find(|m| m.id == id)
 }
 
-/// Build the HuggingFace download URL for a whisper model file.
-pub fn model_url(file_name: &str) -> String {
+/// ID prefix of imported model files in `transcription-mode`.
+pub const CUSTOM_MODEL_PREFIX: &str = "local:";
+
+/// A ggml model file imported from anywhere on disk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomModel {
+    /// `local:` followed by the file name without `.bin`.
+    pub id: String,
+    pub label: String,
+    pub path: PathBuf,
+}
+
+impl CustomModel {
+    /// Describe a ggml `.bin` file for import, after checking it is one.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let is_bin = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("bin"));
+        if !is_bin {
+            return Err(format!("{} is not a .bin model file", path.display()));
+        }
+        let path = std::fs::canonicalize(path)
+            .map_err(|e| format!("Cannot open {}: {e}", path.display()))?;
+        if path.to_str().is_none() {
+            return Err("Model path is not valid UTF-8".into());
+        }
+        let mut header = [0u8; 4];
+        std::fs::File::open(&path)
+            .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut header))
+            .map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+        if !is_ggml_header(&header) {
+            return Err(format!("{} is not a ggml whisper model", path.display()));
+        }
+
+        let label = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let slug: String = label
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || "._-".contains(c) {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        Ok(Self {
+            id: format!("{CUSTOM_MODEL_PREFIX}{slug}"),
+            label,
+            path,
+        })
+    }
+
+    /// This model with an ID no other imported file uses. A file imported
+    /// before keeps its ID; one whose name clashes with another file still
+    /// on disk gets a numbered ID and label ("local:model-2", "model (2)").
+    /// An entry whose file is gone is taken over, as the file was moved.
+    pub fn unique_among(mut self, existing: &[CustomModel]) -> Self {
+        if let Some(same) = existing.iter().find(|m| m.path == self.path) {
+            return same.clone();
+        }
+        let (id, label) = (self.id.clone(), self.label.clone());
+        let mut n = 1;
+        while existing.iter().any(|m| m.id == self.id && m.path.exists()) {
+            n += 1;
+            self.id = format!("{id}-{n}");
+            self.label = format!("{label} ({n})");
+        }
+        self
+    }
+}
+
+/// Whether a file starts with the magic number of whisper.cpp's ggml format.
+pub fn is_ggml_header(header: &[u8]) -> bool {
+    header.starts_with(&0x6767_6d6c_u32.to_le_bytes())
+}
+
+/// Local model named by `WHISPER_MODEL`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LocalModelSpec {
+    /// A built-in preset, by ID.
+    Preset(&'static str),
+    /// A ggml file to import.
+    File(PathBuf),
+}
+
+/// Resolve `WHISPER_MODEL`: a preset ID ("local-small"), a preset file name
+/// ("ggml-small.en.bin"), or the path of a model file. Relative paths are
+/// looked up in `models_dir`, then in the current directory.
+pub fn resolve_model_spec(spec: &str, models_dir: &Path) -> Result<LocalModelSpec, String> {
+    let spec = spec.trim();
+    if let Some(preset) = LOCAL_MODEL_PRESETS
+        .iter()
+        .find(|m| m.id == spec || m.file_name == spec)
+    {
+        return Ok(LocalModelSpec::Preset(preset.id));
+    }
+    let path = Path::new(spec);
+    [models_dir.join(path), path.to_path_buf()]
+        .into_iter()
+        .find(|p| p.is_file())
+        .map(LocalModelSpec::File)
+        .ok_or_else(|| format!("WHISPER_MODEL {spec:?} is neither a model preset nor a file"))
+}
+
+/// Languages offered in the Language menu, as (whisper code, label). Any
+/// code in [`WHISPER_LANGUAGES`] can still be set over D-Bus.
+pub const LANGUAGE_MENU: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("pt", "Portuguese"),
+    ("de", "German"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("it", "Italian"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("uk", "Ukrainian"),
+    ("ru", "Russian"),
+    ("tr", "Turkish"),
+    ("ar", "Arabic"),
+    ("hi", "Hindi"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("zh", "Chinese"),
+];
+
+/// Every language whisper knows as (code, English name), in whisper's own order.
+pub const WHISPER_LANGUAGES: &[(&str, &str)] = &[
+    ("en", "english"),
+    ("zh", "chinese"),
+    ("de", "german"),
+    ("es", "spanish"),
+    ("ru", "russian"),
+    ("ko", "korean"),
+    ("fr", "french"),
+    ("ja", "japanese"),
+    ("pt", "portuguese"),
+    ("tr", "turkish"),
+    ("pl", "polish"),
+    ("ca", "catalan"),
+    ("nl", "dutch"),
+    ("ar", "arabic"),
+    ("sv", "swedish"),
+    ("it", "italian"),
+    ("id", "indonesian"),
+    ("hi", "hindi"),
+    ("fi", "finnish"),
+    ("vi", "vietnamese"),
+    ("he", "hebrew"),
+    ("uk", "ukrainian"),
+    ("el", "greek"),
+    ("ms", "malay"),
+    ("cs", "czech"),
+    ("ro", "romanian"),
+    ("da", "danish"),
+    ("hu", "hungarian"),
+    ("ta", "tamil"),
+    ("no", "norwegian"),
+    ("th", "thai"),
+    ("ur", "urdu"),
+    ("hr", "croatian"),
+    ("bg", "bulgarian"),
+    ("lt", "lithuanian"),
+    ("la", "latin"),
+    ("mi", "maori"),
+    ("ml", "malayalam"),
+    ("cy", "welsh"),
+    ("sk", "slovak"),
+    ("te", "telugu"),
+    ("fa", "persian"),
+    ("lv", "latvian"),
+    ("bn", "bengali"),
+    ("sr", "serbian"),
+    ("az", "azerbaijani"),
+    ("sl", "slovenian"),
+    ("kn", "kannada"),
+    ("et", "estonian"),
+    ("mk", "macedonian"),
+    ("br", "breton"),
+    ("eu", "basque"),
+    ("is", "icelandic"),
+    ("hy", "armenian"),
+    ("ne", "nepali"),
+    ("mn", "mongolian"),
+    ("bs", "bosnian"),
+    ("kk", "kazakh"),
+    ("sq", "albanian"),
+    ("sw", "swahili"),
+    ("gl", "galician"),
+    ("mr", "marathi"),
+    ("pa", "punjabi"),
+    ("si", "sinhala"),
+    ("km", "khmer"),
+    ("sn", "shona"),
+    ("yo", "yoruba"),
+    ("so", "somali"),
+    ("af", "afrikaans"),
+    ("oc", "occitan"),
+    ("ka", "georgian"),
+    ("be", "belarusian"),
+    ("tg", "tajik"),
+    ("sd", "sindhi"),
+    ("gu", "gujarati"),
+    ("am", "amharic"),
+    ("yi", "yiddish"),
+    ("lo", "lao"),
+    ("uz", "uzbek"),
+    ("fo", "faroese"),
+    ("ht", "haitian creole"),
+    ("ps", "pashto"),
+    ("tk", "turkmen"),
+    ("nn", "nynorsk"),
+    ("mt", "maltese"),
+    ("sa", "sanskrit"),
+    ("lb", "luxembourgish"),
+    ("my", "myanmar"),
+    ("bo", "tibetan"),
+    ("tl", "tagalog"),
+    ("mg", "malagasy"),
+    ("as", "assamese"),
+    ("tt", "tatar"),
+    ("haw", "hawaiian"),
+    ("ln", "lingala"),
+    ("ha", "hausa"),
+    ("ba", "bashkir"),
+    ("jw", "javanese"),
+    ("su", "sundanese"),
+    ("yue", "cantonese"),
+];
+
+/// Parse a language setting: "auto" (or empty) detects the language per
+/// recording and gives `None`; otherwise a whisper language code.
+pub fn parse_language(value: &str) -> Result<Option<String>, String> {
+    let value = value.trim().to_ascii_lowercase();
+    if value.is_empty() || value == "auto" {
+        return Ok(None);
+    }
+    if WHISPER_LANGUAGES.iter().any(|(code, _)| *code == value) {
+        Ok(Some(value))
+    } else {
+        Err(format!("Unknown language code: {value}"))
+    }
+}
+
+/// Whisper code for a language given by code or English name, as API
+/// backends report it ("pt", "Portuguese").
+pub fn language_code(name_or_code: &str) -> Option<&'static str> {
+    let value = name_or_code.trim().to_ascii_lowercase();
+    WHISPER_LANGUAGES
+        .iter()
+        .find(|(code, name)| *code == value || *name == value)
+        .map(|(code, _)| *code)
+}
+
+/// HuggingFace, where models and voices are downloaded from unless
+/// `HF_ENDPOINT` names a mirror.
+pub const DEFAULT_HF_ENDPOINT: &str = "https://huggingface.co";
+
+/// Download URL of a file in a HuggingFace repository on `endpoint`.
+pub fn huggingface_url(endpoint: &str, repo: &str, path: &str) -> String {
     format!(
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}",
-        file_name
+        "{}/{repo}/resolve/main/{path}",
+        endpoint.trim_end_matches('/')
     )
 }
 
+/// Build the HuggingFace download URL for a whisper model file.
+pub fn model_url(endpoint: &str, file_name: &str) -> String {
+    huggingface_url(endpoint, "ggerganov/whisper.cpp", file_name)
+}
+
 // ── TTS (text-to-speech) ────────────────────────────────────────────────────
 
 /// Active TTS provider.
@@ -121,19 +554,32 @@ pub struct PiperVoice {
     pub locale: &'static str,
     pub name: &'static str,
     pub quality: &'static str,
+    /// SHA-256 of the `.onnx` and `.onnx.json` files, or empty when not
+    /// pinned yet.
+    pub onnx_sha256: &'static str,
+    pub json_sha256: &'static str,
 }
 
 impl PiperVoice {
     /// HuggingFace URL for the ONNX model file.
-    pub fn onnx_url(&self) -> String {
-        format!(
-            "https://huggingface.co/rhasspy/piper-voices/resolve/main/en/{}/{}/{}/{}-{}-{}.onnx",
+    pub fn onnx_url(&self, endpoint: &str) -> String {
+        let path = format!(
+            "en/{}/{}/{}/{}-{}-{}.onnx",
             self.locale, self.name, self.quality, self.locale, self.name, self.quality
-        )
+        );
+        huggingface_url(endpoint, "rhasspy/piper-voices", &path)
     }
     /// HuggingFace URL for the ONNX config file.
-    pub fn config_url(&self) -> String {
-        format!("{}.json", self.onnx_url())
+    pub fn config_url(&self, endpoint: &str) -> String {
+        format!("{}.json", self.onnx_url(endpoint))
+    }
+    /// The pinned SHA-256 of the ONNX model file, if any.
+    pub fn onnx_pinned_sha256(&self) -> Option<&'static str> {
+        pinned(self.onnx_sha256)
+    }
+    /// The pinned SHA-256 of the ONNX config file, if any.
+    pub fn json_pinned_sha256(&self) -> Option<&'static str> {
+        pinned(self.json_sha256)
     }
 }
 
@@ -145,6 +591,8 @@ pub const PIPER_VOICES: &[PiperVoice] = &[
         locale: "en_US",
         name: "amy",
         quality: "medium",
+        onnx_sha256: "",
+        json_sha256: "",
     },
     PiperVoice {
         id: "lessac",
@@ -152,6 +600,8 @@ pub const PIPER_VOICES: &[PiperVoice] = &[
         locale: "en_US",
         name: "lessac",
         quality: "medium",
+        onnx_sha256: "",
+        json_sha256: "",
     },
     PiperVoice {
         id: "ryan",
@@ -159,6 +609,8 @@ pub const PIPER_VOICES: &[PiperVoice] = &[
         locale: "en_US",
         name: "ryan",
         quality: "medium",
+        onnx_sha256: "",
+        json_sha256: "",
     },
     PiperVoice {
         id: "kristin",
@@ -166,6 +618,8 @@ pub const PIPER_VOICES: &[PiperVoice] = &[
         locale: "en_US",
         name: "kristin",
         quality: "medium",
+        onnx_sha256: "",
+        json_sha256: "",
     },
     PiperVoice {
         id: "joe",
@@ -173,6 +627,8 @@ pub const PIPER_VOICES: &[PiperVoice] = &[
         locale: "en_US",
         name: "joe",
         quality: "medium",
+        onnx_sha256: "",
+        json_sha256: "",
     },
     PiperVoice {
         id: "cori",
@@ -180,6 +636,8 @@ pub const PIPER_VOICES: &[PiperVoice] = &[
         locale: "en_GB",
         name: "cori",
         quality: "medium",
+        onnx_sha256: "",
+        json_sha256: "",
     },
 ];
 
@@ -202,6 +660,248 @@ pub fn piper_voice_exists(piper_dir: &std::path::Path, voice_id: &str) -> bool {
         && piper_dir.join(format!("{voice_id}.onnx.json")).exists()
 }
 
+/// Where recordings come from (`AUDIO_SOURCE` or `--audio-source`).
+/// Everything but `Device` works without a sound card.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AudioSourceSpec {
+    /// Live capture from an input device (the default).
+    Device,
+    /// A WAV file, or headerless PCM in the raw format, played back in real time.
+    File(PathBuf),
+    /// Headerless PCM piped on stdin.
+    Stdin,
+    /// A generated sine tone at the given frequency in Hz.
+    Tone(f32),
+}
+
+/// Frequency of the test tone when none is given.
+pub const DEFAULT_TONE_HZ: f32 = 440.0;
+
+impl AudioSourceSpec {
+    /// Parse `device`, `file:PATH`, `stdin` or `tone[:HZ]`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec.split_once(':') {
+            None => match spec {
+                "" | "device" => Ok(Self::Device),
+                "stdin" => Ok(Self::Stdin),
+                "tone" => Ok(Self::Tone(DEFAULT_TONE_HZ)),
+                _ => Err(format!(
+                    "Unknown audio source {spec:?} (expected device, file:PATH, stdin or tone[:HZ])"
+                )),
+            },
+            Some(("file", path)) if !path.is_empty() => Ok(Self::File(PathBuf::from(path))),
+            Some(("tone", hz)) => hz
+                .parse::<f32>()
+                .ok()
+                .filter(|f| *f > 0.0 && *f < 20000.0)
+                .map(Self::Tone)
+                .ok_or_else(|| format!("Invalid tone frequency: {hz:?}")),
+            _ => Err(format!(
+                "Unknown audio source {spec:?} (expected device, file:PATH, stdin or tone[:HZ])"
+            )),
+        }
+    }
+}
+
+/// Layout of headerless PCM (stdin and raw files): signed 16-bit little-endian
+/// at this rate and channel count.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawPcmFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Default raw PCM layout: 16 kHz mono, what whisper consumes.
+pub const DEFAULT_RAW_PCM: RawPcmFormat = RawPcmFormat {
+    sample_rate: 16000,
+    channels: 1,
+};
+
+/// Default trailing silence before auto-stop ends a recording.
+pub const DEFAULT_VAD_SILENCE_MS: u32 = 1500;
+
+/// Default maximum recording length (10 minutes).
+pub const DEFAULT_MAX_RECORDING_SECS: u32 = 600;
+
+/// Default length of audio kept ahead of a recording while the mic is armed.
+pub const DEFAULT_PRE_ROLL_MS: u32 = 1000;
+
+/// Default disk budget for retained recordings, in megabytes.
+pub const DEFAULT_RETAIN_AUDIO_MAX_MB: u32 = 200;
+
+/// Default age after which retained recordings are dropped.
+pub const DEFAULT_RETAIN_AUDIO_MAX_DAYS: u32 = 30;
+
+/// Default disk budget for downloaded whisper models (0 = unlimited).
+pub const DEFAULT_MODELS_MAX_MB: u32 = 0;
+
+/// How whisper.cpp decodes speech with a local model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodingProfile {
+    /// Beam width; 1 decodes greedily.
+    pub beam_size: u32,
+    /// CPU threads used for inference.
+    pub threads: u32,
+    /// Sampling temperature of the first attempt.
+    pub temperature: f32,
+    /// Added to the temperature to retry a stretch that fails whisper's
+    /// entropy and log-probability checks. 0 disables the fallback.
+    pub temperature_inc: f32,
+    /// Decode each 30 s window without the text of the previous one.
+    pub no_context: bool,
+    /// Keep segments from starting with a blank.
+    pub suppress_blank: bool,
+    /// Encoder context in 20 ms frames (0 = the full 1500). Smaller is
+    /// faster but drops audio past its end in each window.
+    pub audio_ctx: u32,
+}
+
+/// Decoding presets: ID and menu label.
+pub const DECODING_PRESETS: &[(&str, &str)] = &[
+    ("fast", "Fast"),
+    ("balanced", "Balanced"),
+    ("accurate", "Accurate"),
+];
+
+/// Preset used until one is chosen.
+pub const DEFAULT_DECODING_PRESET: &str = "balanced";
+
+/// Widest beam accepted.
+pub const MAX_BEAM_SIZE: u32 = 16;
+
+/// Encoder context of a full 30 s window.
+pub const MAX_AUDIO_CTX: u32 = 1500;
+
+/// Most threads a live preview pass uses, leaving the rest of the CPU to
+/// recording and the desktop.
+pub const PREVIEW_THREADS: u32 = 2;
+
+impl DecodingProfile {
+    /// A preset by ID, running on every physical core.
+    pub fn preset(id: &str) -> Option<Self> {
+        let threads = physical_cores();
+        match id {
+            // Greedy with no retries: one pass per window
+            "fast" => Some(Self {
+                beam_size: 1,
+                threads,
+                temperature: 0.0,
+                temperature_inc: 0.0,
+                no_context: true,
+                suppress_blank: true,
+                audio_ctx: 0,
+            }),
+            // whisper.cpp's own defaults
+            "balanced" => Some(Self {
+                beam_size: 1,
+                threads,
+                temperature: 0.0,
+                temperature_inc: 0.2,
+                no_context: true,
+                suppress_blank: true,
+                audio_ctx: 0,
+            }),
+            // What the reference implementation uses for best results
+            "accurate" => Some(Self {
+                beam_size: 5,
+                threads,
+                temperature: 0.0,
+                temperature_inc: 0.2,
+                no_context: false,
+                suppress_blank: true,
+                audio_ctx: 0,
+            }),
+            _ => None,
+        }
+    }
+
+    /// This profile cut down for live preview passes, which run over and
+    /// over while recording: greedy, no fallback, and a few threads at most.
+    pub fn for_preview(mut self) -> Self {
+        self.beam_size = 1;
+        self.temperature_inc = 0.0;
+        self.threads = self.threads.clamp(1, PREVIEW_THREADS);
+        self
+    }
+
+    /// This profile with any values set in `overrides`.
+    pub fn with_overrides(mut self, overrides: &DecodingOverrides) -> Self {
+        if let Some(beam_size) = overrides.beam_size {
+            self.beam_size = beam_size;
+        }
+        if let Some(threads) = overrides.threads {
+            self.threads = threads;
+        }
+        if let Some(temperature) = overrides.temperature {
+            self.temperature = temperature;
+        }
+        if let Some(temperature_inc) = overrides.temperature_inc {
+            self.temperature_inc = temperature_inc;
+        }
+        if let Some(no_context) = overrides.no_context {
+            self.no_context = no_context;
+        }
+        if let Some(suppress_blank) = overrides.suppress_blank {
+            self.suppress_blank = suppress_blank;
+        }
+        if let Some(audio_ctx) = overrides.audio_ctx {
+            self.audio_ctx = audio_ctx;
+        }
+        self
+    }
+}
+
+impl Default for DecodingProfile {
+    fn default() -> Self {
+        Self::preset(DEFAULT_DECODING_PRESET).expect("default decoding preset exists")
+    }
+}
+
+/// Decoding values pinned in the environment, applied over whichever
+/// preset is chosen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DecodingOverrides {
+    pub beam_size: Option<u32>,
+    pub threads: Option<u32>,
+    pub temperature: Option<f32>,
+    pub temperature_inc: Option<f32>,
+    pub no_context: Option<bool>,
+    pub suppress_blank: Option<bool>,
+    pub audio_ctx: Option<u32>,
+}
+
+/// Physical CPU cores (without SMT siblings), capped at the CPUs this
+/// process may run on.
+pub fn physical_cores() -> u32 {
+    let logical = std::thread::available_parallelism().map_or(4, |n| n.get());
+    let physical = std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| count_physical_cores(&info))
+        .unwrap_or(logical);
+    physical.min(logical).max(1) as u32
+}
+
+/// Distinct cores in `/proc/cpuinfo` text. `None` when it doesn't list
+/// them (as on most ARM kernels).
+pub fn count_physical_cores(cpuinfo: &str) -> Option<usize> {
+    let mut cores = std::collections::HashSet::new();
+    let mut package = "";
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "processor" => package = "",
+            "physical id" => package = value.trim(),
+            "core id" => {
+                cores.insert((package, value.trim()));
+            }
+            _ => {}
+        }
+    }
+    (!cores.is_empty()).then_some(cores.len())
+}
+
 /// Application configuration loaded from environment and `.env` file.
 pub struct Config {
     pub transcription_service: TranscriptionService,
@@ -210,7 +910,32 @@ pub struct Config {
     pub api_model: String,
     pub db_path: PathBuf,
     pub models_dir: PathBuf,
+    /// Local model from `WHISPER_MODEL`, used when none is picked in the menu.
+    pub whisper_model: Option<LocalModelSpec>,
+    /// Total size of downloaded models in MB before the least recently used
+    /// are deleted (0 = unlimited).
+    pub models_max_mb: u32,
+    /// Base URL models and voices are downloaded from (`HF_ENDPOINT`).
+    pub hf_endpoint: String,
     pub sound_notification: bool,
+    /// Trailing silence (ms) that ends a recording when auto-stop is on.
+    pub vad_silence_ms: u32,
+    /// Longest recording in seconds before it auto-stops (0 = unlimited).
+    pub max_recording_secs: u32,
+    /// Total size of retained recordings in MB (0 = unlimited).
+    pub retain_audio_max_mb: u32,
+    /// Age in days after which retained recordings are dropped (0 = never).
+    pub retain_audio_max_days: u32,
+    /// Audio (ms) prepended to a recording when pre-roll is on.
+    pub pre_roll_ms: u32,
+    /// Where recordings come from (sound card unless overridden).
+    pub audio_source: AudioSourceSpec,
+    /// Layout of headerless PCM for the stdin and raw file sources.
+    pub raw_pcm: RawPcmFormat,
+    /// Decoding preset used until one is picked in the menu.
+    pub decoding_preset: String,
+    /// Decoding values that override the chosen preset.
+    pub decoding_overrides: DecodingOverrides,
 }
 
 impl Config {
@@ -259,10 +984,119 @@ impl Config {
         let models_dir = data_dir.join("models");
         std::fs::create_dir_all(&models_dir).ok();
 
+        let whisper_model = std::env::var("WHISPER_MODEL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .and_then(|spec| {
+                resolve_model_spec(&spec, &models_dir)
+                    .inspect_err(|e| eprintln!("{e}, using the default model"))
+                    .ok()
+            });
+
+        let models_max_mb = std::env::var("MODELS_MAX_MB")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_MODELS_MAX_MB);
+
+        let hf_endpoint = match std::env::var("HF_ENDPOINT") {
+            Ok(url) if url.starts_with("https://") || url.starts_with("http://") => {
+                url.trim_end_matches('/').to_string()
+            }
+            Ok(url) if !url.trim().is_empty() => {
+                eprintln!("HF_ENDPOINT must be an http(s) URL, got {url:?}; using HuggingFace");
+                DEFAULT_HF_ENDPOINT.into()
+            }
+            _ => DEFAULT_HF_ENDPOINT.into(),
+        };
+
         let sound_notification = std::env::var("SOUND_NOTIFICATION_ON_COMPLETION")
             .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
             .unwrap_or(false);
 
+        let vad_silence_ms = std::env::var("VAD_SILENCE_MS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|&ms| ms > 0)
+            .unwrap_or(DEFAULT_VAD_SILENCE_MS);
+
+        let max_recording_secs = std::env::var("MAX_RECORDING_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_MAX_RECORDING_SECS);
+
+        let retain_audio_max_mb = std::env::var("RETAIN_AUDIO_MAX_MB")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_RETAIN_AUDIO_MAX_MB);
+
+        let retain_audio_max_days = std::env::var("RETAIN_AUDIO_MAX_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_RETAIN_AUDIO_MAX_DAYS);
+
+        let pre_roll_ms = std::env::var("PRE_ROLL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|&ms| ms > 0 && ms <= 10_000)
+            .unwrap_or(DEFAULT_PRE_ROLL_MS);
+
+        let audio_source = match std::env::var("AUDIO_SOURCE") {
+            Ok(spec) => AudioSourceSpec::parse(&spec).unwrap_or_else(|e| {
+                eprintln!("{e}, using the sound card");
+                AudioSourceSpec::Device
+            }),
+            Err(_) => AudioSourceSpec::Device,
+        };
+
+        let raw_pcm = RawPcmFormat {
+            sample_rate: std::env::var("RAW_PCM_RATE")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .filter(|&rate| (8000..=192_000).contains(&rate))
+                .unwrap_or(DEFAULT_RAW_PCM.sample_rate),
+            channels: std::env::var("RAW_PCM_CHANNELS")
+                .ok()
+                .and_then(|v| v.parse::<u16>().ok())
+                .filter(|&ch| (1..=8).contains(&ch))
+                .unwrap_or(DEFAULT_RAW_PCM.channels),
+        };
+
+        let decoding_preset = std::env::var("DECODING_PROFILE")
+            .ok()
+            .map(|v| v.to_lowercase())
+            .filter(|v| DecodingProfile::preset(v).is_some())
+            .unwrap_or_else(|| DEFAULT_DECODING_PRESET.into());
+
+        let env_bool = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        };
+        let decoding_overrides = DecodingOverrides {
+            beam_size: std::env::var("WHISPER_BEAM_SIZE")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .filter(|n| (1..=MAX_BEAM_SIZE).contains(n)),
+            threads: std::env::var("WHISPER_THREADS")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .filter(|&n| n > 0),
+            temperature: std::env::var("WHISPER_TEMPERATURE")
+                .ok()
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|t| (0.0..=1.0).contains(t)),
+            temperature_inc: std::env::var("WHISPER_TEMPERATURE_INC")
+                .ok()
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|t| (0.0..=1.0).contains(t)),
+            no_context: env_bool("WHISPER_NO_CONTEXT"),
+            suppress_blank: env_bool("WHISPER_SUPPRESS_BLANK"),
+            audio_ctx: std::env::var("WHISPER_AUDIO_CTX")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .filter(|&n| n <= MAX_AUDIO_CTX),
+        };
+
         Self {
             transcription_service,
             api_base_url,
@@ -270,7 +1104,19 @@ impl Config {
             api_model,
             db_path,
             models_dir,
+            whisper_model,
+            models_max_mb,
+            hf_endpoint,
             sound_notification,
+            vad_silence_ms,
+            max_recording_secs,
+            retain_audio_max_mb,
+            retain_audio_max_days,
+            pre_roll_ms,
+            audio_source,
+            raw_pcm,
+            decoding_preset,
+            decoding_overrides,
         }
     }
 }
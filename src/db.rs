@@ -1,4 +1,7 @@
+use crate::config::CustomModel;
+use crate::transcript::Segment;
 use rusqlite::{Connection, Result, params};
+use std::collections::HashMap;
 use std::path::Path;
 
 /// Allowed setting keys (prevents arbitrary key/value injection).
@@ -13,8 +16,24 @@ const VALID_SETTING_PREFIXES: &[&str] = &[
     "api_key_",
     "tts_provider",
     "tts_voice",
+    "input_device",
+    "audio_host",
+    "input_channels",
+    "auto_stop",
+    "live_preview",
+    "record_mode",
+    "preprocess_",
+    "retain_audio",
+    "pre_roll",
+    "language",
+    "translate",
+    "carry_context",
+    "decoding_profile",
 ];
 
+/// Longest glossary term kept.
+const MAX_GLOSSARY_TERM_CHARS: usize = 100;
+
 /// SQLite database for transcription history and settings.
 pub struct Db {
     conn: Connection,
@@ -22,14 +41,22 @@ pub struct Db {
 
 /// A single transcription record.
 pub struct Transcription {
-    pub _id: i64,
+    pub id: i64,
     pub text: String,
     pub created_at: String,
+    /// Whether a retained recording is stored for this entry.
+    pub has_audio: bool,
+    /// Spoken language (whisper code), when the backend reported one.
+    pub language: Option<String>,
+    /// Whether segment timings are stored, so subtitles can be exported.
+    pub has_segments: bool,
 }
 
 impl Db {
     pub fn open(path: &Path) -> Result<Self> {
         let conn = Connection::open(path)?;
+        // Off by default in SQLite; recordings rely on ON DELETE CASCADE
+        conn.pragma_update(None, "foreign_keys", true)?;
         conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS transcriptions (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -39,19 +66,150 @@ impl Db {
             CREATE TABLE IF NOT EXISTS settings (
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS recordings (
+                transcription_id INTEGER PRIMARY KEY REFERENCES transcriptions(id) ON DELETE CASCADE,
+                audio BLOB NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+            );
+            CREATE TABLE IF NOT EXISTS glossary (
+                term TEXT PRIMARY KEY COLLATE NOCASE
+            );
+            CREATE TABLE IF NOT EXISTS custom_models (
+                id TEXT PRIMARY KEY,
+                label TEXT NOT NULL,
+                path TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS model_usage (
+                id TEXT PRIMARY KEY,
+                last_used TEXT NOT NULL
             );",
         )?;
+        // Columns added after the first release
+        for column in ["language", "segments"] {
+            if conn
+                .prepare(&format!("SELECT {column} FROM transcriptions LIMIT 0"))
+                .is_err()
+            {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE transcriptions ADD COLUMN {column} TEXT"
+                ))?;
+            }
+        }
         Ok(Self { conn })
     }
 
     pub fn insert(&self, text: &str) -> Result<i64> {
+        self.insert_with_language(text, None)
+    }
+
+    /// Insert an entry along with the language it was spoken in.
+    pub fn insert_with_language(&self, text: &str, language: Option<&str>) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO transcriptions (text) VALUES (?1)",
-            params![text],
+            "INSERT INTO transcriptions (text, language) VALUES (?1, ?2)",
+            params![text, language],
         )?;
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// Record the language of an existing entry (after re-transcription).
+    pub fn set_language(&self, id: i64, language: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcriptions SET language = ?2 WHERE id = ?1",
+            params![id, language],
+        )?;
+        Ok(())
+    }
+
+    /// Replace the text of an existing entry (after re-transcription).
+    pub fn update_text(&self, id: i64, text: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcriptions SET text = ?2 WHERE id = ?1",
+            params![id, text],
+        )?;
+        Ok(())
+    }
+
+    /// Store the segment timings of an entry, used for subtitle export.
+    pub fn set_segments(&self, id: i64, segments: &[Segment]) -> Result<()> {
+        let json = serde_json::to_string(segments)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.conn.execute(
+            "UPDATE transcriptions SET segments = ?2 WHERE id = ?1",
+            params![id, json],
+        )?;
+        Ok(())
+    }
+
+    /// Segment timings of an entry; empty when none were stored.
+    pub fn segments(&self, id: i64) -> Result<Vec<Segment>> {
+        let json: Option<String> = self.conn.query_row(
+            "SELECT segments FROM transcriptions WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        Ok(json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    /// Store the compressed recording for a transcription.
+    pub fn save_recording(&self, transcription_id: i64, audio: &[u8]) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO recordings (transcription_id, audio) VALUES (?1, ?2)",
+            params![transcription_id, audio],
+        )?;
+        Ok(())
+    }
+
+    /// Compressed recording for a transcription, if one was retained.
+    pub fn recording(&self, transcription_id: i64) -> Result<Option<Vec<u8>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT audio FROM recordings WHERE transcription_id = ?1")?;
+        let mut rows = stmt.query_map(params![transcription_id], |row| row.get(0))?;
+        match rows.next() {
+            Some(Ok(audio)) => Ok(Some(audio)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Drop recordings older than `max_age_days`, then the oldest ones until
+    /// the rest fit in `max_bytes`. Zero disables either limit. The newest
+    /// recording is always kept by the size limit, even when it alone is
+    /// larger, so the one just saved can still be replayed.
+    /// Returns the number of recordings removed.
+    pub fn prune_recordings(&self, max_bytes: u64, max_age_days: u32) -> Result<usize> {
+        let mut removed = 0;
+        if max_age_days > 0 {
+            removed += self.conn.execute(
+                "DELETE FROM recordings WHERE created_at < datetime('now','localtime',?1)",
+                params![format!("-{max_age_days} days")],
+            )?;
+        }
+        if max_bytes > 0 {
+            let sizes: Vec<(i64, u64)> = {
+                let mut stmt = self.conn.prepare(
+                    "SELECT transcription_id, length(audio) FROM recordings
+                     ORDER BY transcription_id DESC",
+                )?;
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<Result<_>>()?
+            };
+            let mut total = 0u64;
+            for (i, (id, size)) in sizes.into_iter().enumerate() {
+                total += size;
+                if i > 0 && total > max_bytes {
+                    removed += self.conn.execute(
+                        "DELETE FROM recordings WHERE transcription_id = ?1",
+                        params![id],
+                    )?;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
     pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
         let mut stmt = self
             .conn
@@ -83,15 +241,106 @@ impl Db {
         Ok(())
     }
 
-    pub fn recent(&self, limit: usize) -> Result<Vec<Transcription>> {
+    /// Glossary terms, in the order they were added.
+    pub fn glossary(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT term FROM glossary ORDER BY rowid")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// Add a glossary term. Returns false when it was already there
+    /// (ignoring case) or is blank.
+    pub fn add_glossary_term(&self, term: &str) -> Result<bool> {
+        let term = term.trim();
+        if term.is_empty() {
+            return Ok(false);
+        }
+        // Cap term length like setting values
+        let term: String = term.chars().take(MAX_GLOSSARY_TERM_CHARS).collect();
+        let added = self.conn.execute(
+            "INSERT OR IGNORE INTO glossary (term) VALUES (?1)",
+            params![term],
+        )?;
+        Ok(added > 0)
+    }
+
+    /// Remove a glossary term (ignoring case). Returns whether it existed.
+    pub fn remove_glossary_term(&self, term: &str) -> Result<bool> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM glossary WHERE term = ?1", params![term.trim()])?;
+        Ok(removed > 0)
+    }
+
+    /// Imported model files, in the order they were imported.
+    pub fn custom_models(&self) -> Result<Vec<CustomModel>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT id, text, created_at FROM transcriptions ORDER BY id DESC LIMIT ?1")?;
+            .prepare("SELECT id, label, path FROM custom_models ORDER BY rowid")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(CustomModel {
+                id: row.get(0)?,
+                label: row.get(1)?,
+                path: row.get::<_, String>(2)?.into(),
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Register an imported model file, replacing one with the same ID.
+    pub fn add_custom_model(&self, model: &CustomModel) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO custom_models (id, label, path) VALUES (?1, ?2, ?3)
+             ON CONFLICT(id) DO UPDATE SET label = excluded.label, path = excluded.path",
+            params![model.id, model.label, model.path.to_string_lossy()],
+        )?;
+        Ok(())
+    }
+
+    /// Forget an imported model (the file itself is left alone).
+    /// Returns whether it was registered.
+    pub fn remove_custom_model(&self, id: &str) -> Result<bool> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM custom_models WHERE id = ?1", params![id])?;
+        Ok(removed > 0)
+    }
+
+    /// Record that a local model was used just now.
+    pub fn touch_model(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO model_usage (id, last_used) VALUES (?1, datetime('now','localtime'))
+             ON CONFLICT(id) DO UPDATE SET last_used = excluded.last_used",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// When each local model was last used (local time), by model ID.
+    pub fn model_usage(&self) -> Result<HashMap<String, String>> {
+        let mut stmt = self.conn.prepare("SELECT id, last_used FROM model_usage")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    pub fn recent(&self, limit: usize) -> Result<Vec<Transcription>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.id, t.text, t.created_at, r.transcription_id IS NOT NULL, t.language,
+                    t.segments IS NOT NULL AND t.segments != '[]'
+                 FROM transcriptions t
+                 LEFT JOIN recordings r ON r.transcription_id = t.id
+                 ORDER BY t.id DESC LIMIT ?1",
+        )?;
         let rows = stmt.query_map(params![limit as i64], |row| {
             Ok(Transcription {
-                _id: row.get(0)?,
+                id: row.get(0)?,
                 text: row.get(1)?,
                 created_at: row.get(2)?,
+                has_audio: row.get(3)?,
+                language: row.get(4)?,
+                has_segments: row.get(5)?,
             })
         })?;
         rows.collect()
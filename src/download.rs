@@ -0,0 +1,357 @@
+//! Resumable model and voice downloads from HuggingFace or a mirror.
+//!
+//! A file is fetched into `<name>.part` next to its destination. A failed
+//! download keeps what it got, and the next attempt asks for the rest with
+//! an HTTP Range request. Before it is renamed into place, the finished file
+//! is checked against its SHA-256 pinned in [`crate::config`] when there is
+//! one. Otherwise it is checked against the SHA-256 huggingface.co publishes
+//! for it (the `X-Linked-Etag` of LFS files), looked up there even when the
+//! file comes from a mirror. A file with neither is only checked for length.
+
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Relative redirects followed while looking up a file's checksum (renamed
+/// repositories redirect to their new name).
+const MAX_REDIRECTS: usize = 5;
+
+/// Where a download is kept until it is complete and verified.
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// The SHA-256 in an ETag header value (`"<64 hex digits>"`, possibly weak),
+/// lowercased. `None` for other ETags, like the git hashes of small files.
+pub fn sha256_from_etag(etag: &str) -> Option<String> {
+    let value = etag.trim().trim_start_matches("W/").trim_matches('"');
+    (value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| value.to_ascii_lowercase())
+}
+
+/// First byte and total size from `Content-Range: bytes <first>-<last>/<total>`.
+/// The total is `None` when the server sends `*`.
+pub fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (span, total) = value.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let (first, _) = span.split_once('-')?;
+    Some((first.trim().parse().ok()?, total.trim().parse().ok()))
+}
+
+/// What to do with a `.part` file after asking for the bytes past its end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resume {
+    /// 206: the body continues the file.
+    Append,
+    /// 200: the server ignored the range and sent the whole file.
+    Restart,
+    /// 416 and the part is already the full size.
+    Complete,
+    /// 416 and the part doesn't match the file (it changed on the server):
+    /// delete it and ask again from the start.
+    Discard,
+}
+
+/// How to continue a `.part` file of `offset` bytes, from the status and
+/// `Content-Range` of the reply to a `Range: bytes=<offset>-` request.
+pub fn resume_action(
+    status: u16,
+    content_range: Option<&str>,
+    offset: u64,
+) -> Result<Resume, String> {
+    match status {
+        206 => match content_range.and_then(parse_content_range) {
+            Some((first, _)) if first == offset => Ok(Resume::Append),
+            _ => Err(format!(
+                "Server resumed at the wrong byte (asked for {offset})"
+            )),
+        },
+        200 => Ok(Resume::Restart),
+        416 if offset > 0 => {
+            let total = content_range
+                .and_then(|v| v.trim().strip_prefix("bytes */"))
+                .and_then(|t| t.parse::<u64>().ok());
+            Ok(if total == Some(offset) {
+                Resume::Complete
+            } else {
+                Resume::Discard
+            })
+        }
+        _ => Err(format!("Download failed: HTTP {status}")),
+    }
+}
+
+/// Lowercase hex SHA-256 of a file.
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    hash_file_into(&mut context, path)?;
+    Ok(hex(context.finish().as_ref()))
+}
+
+fn hash_file_into(context: &mut ring::digest::Context, path: &Path) -> Result<(), String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open file: {e}"))?;
+    let mut buf = [0u8; 65536];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("File read error: {e}"))?;
+        if n == 0 {
+            return Ok(());
+        }
+        context.update(&buf[..n]);
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// The SHA-256 the server publishes for `url`, from a HEAD request that
+/// stops at the first redirect to another host (the CDN, whose ETags are
+/// its own). `None` when the server gives no SHA-256 ETag, and when the
+/// HEAD request fails (some mirrors answer it with 405): the GET that
+/// follows reports a server that is really down.
+fn published_sha256(url: &str) -> Option<String> {
+    let client = reqwest::blocking::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .ok()?;
+    let mut url = reqwest::Url::parse(url).ok()?;
+    for _ in 0..=MAX_REDIRECTS {
+        let resp = client.head(url.clone()).send().ok()?;
+        let status = resp.status();
+        if !status.is_success() && !status.is_redirection() {
+            return None;
+        }
+        let header = |name: &str| resp.headers().get(name).and_then(|v| v.to_str().ok());
+        if let Some(sha256) = header("x-linked-etag")
+            .or(header("etag"))
+            .and_then(sha256_from_etag)
+        {
+            return Some(sha256);
+        }
+        let location = header("location").filter(|_| status.is_redirection())?;
+        let next = url.join(location).ok()?;
+        if next.host_str() != url.host_str() {
+            return None;
+        }
+        url = next;
+    }
+    None
+}
+
+/// What [`fetch`] checks a finished file against.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Checksum {
+    /// A known SHA-256 the file must match.
+    Pinned(String),
+    /// The SHA-256 published at this URL, if any (the file's address on
+    /// huggingface.co, not on the mirror it is downloaded from).
+    Published(String),
+}
+
+impl Checksum {
+    /// `pinned` when there is one, else whatever is published at `url`.
+    pub fn pinned_or_published(pinned: Option<&str>, url: String) -> Self {
+        match pinned {
+            Some(sha256) => Self::Pinned(sha256.to_string()),
+            None => Self::Published(url),
+        }
+    }
+}
+
+/// Error returned by [`fetch`] when it was cancelled.
+pub const CANCELLED: &str = "Download cancelled";
+
+/// Download `url` to `dest`, resuming a `.part` file left by an earlier
+/// attempt. `on_progress` gets the bytes on disk and the total size.
+///
+/// The file must match its `checksum`. A published one that can't be
+/// looked up is logged, and the file is then only checked for length.
+///
+/// A failed transfer keeps the part for next time; a file that doesn't
+/// match its published checksum is deleted so the retry starts over.
+/// Setting `cancel` stops the transfer and deletes the part.
+pub fn fetch(
+    url: &str,
+    dest: &Path,
+    checksum: &Checksum,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<(), String> {
+    let part = part_path(dest);
+    let cancelled = || {
+        let _ = std::fs::remove_file(&part);
+        Err(CANCELLED.to_string())
+    };
+    let expected = match checksum {
+        Checksum::Pinned(sha256) => Some(sha256.to_ascii_lowercase()),
+        Checksum::Published(lookup) => {
+            let published = published_sha256(lookup);
+            if published.is_none() {
+                eprintln!("{url}: no published SHA-256 at {lookup}, checking length only");
+            }
+            published
+        }
+    };
+    if cancel.load(Ordering::Relaxed) {
+        return cancelled();
+    }
+    let client = reqwest::blocking::Client::new();
+
+    let mut offset = std::fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    let (resp, resume) = loop {
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+        }
+        let resp = request
+            .send()
+            .map_err(|e| format!("Download request failed: {e}"))?;
+        let content_range = resp
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok());
+        match resume_action(resp.status().as_u16(), content_range, offset)? {
+            Resume::Discard => {
+                let _ = std::fs::remove_file(&part);
+                offset = 0;
+            }
+            resume => break (resp, resume),
+        }
+    };
+
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    let (mut downloaded, total) = match resume {
+        Resume::Append => {
+            hash_file_into(&mut context, &part)?;
+            let total = resp
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_content_range)
+                .and_then(|(_, total)| total)
+                .or(resp.content_length().map(|len| offset + len));
+            (offset, total)
+        }
+        Resume::Complete => {
+            hash_file_into(&mut context, &part)?;
+            (offset, Some(offset))
+        }
+        _ => (0, resp.content_length()),
+    };
+    on_progress(downloaded, total);
+
+    if resume != Resume::Complete {
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true);
+        if resume == Resume::Append {
+            options.append(true);
+        } else {
+            options.write(true).truncate(true);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&part)
+            .map_err(|e| format!("Failed to create file: {e}"))?;
+
+        let mut reader = resp;
+        let mut buf = [0u8; 65536];
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                drop(file);
+                return cancelled();
+            }
+            let n = reader
+                .read(&mut buf)
+                .map_err(|e| format!("Download read error: {e}"))?;
+            if n == 0 {
+                break;
+            }
+            file.write_all(&buf[..n])
+                .map_err(|e| format!("File write error: {e}"))?;
+            context.update(&buf[..n]);
+            downloaded += n as u64;
+            on_progress(downloaded, total);
+        }
+        if let Some(total) = total.filter(|&t| t != downloaded) {
+            return Err(format!(
+                "Download ended early ({downloaded} of {total} bytes)"
+            ));
+        }
+    }
+
+    let actual = hex(context.finish().as_ref());
+    if let Some(expected) = expected.filter(|e| *e != actual) {
+        let _ = std::fs::remove_file(&part);
+        return Err(format!(
+            "Checksum mismatch: expected SHA-256 {expected}, got {actual}"
+        ));
+    }
+
+    std::fs::rename(&part, dest).map_err(|e| format!("Failed to rename downloaded file: {e}"))
+}
+
+/// How far back [`TransferRate`] looks when measuring speed.
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Download speed over the last few seconds.
+#[derive(Default)]
+pub struct TransferRate {
+    /// When each progress report arrived, with the bytes downloaded by then.
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl TransferRate {
+    /// Note that `downloaded` bytes were on disk at `at`.
+    pub fn record(&mut self, at: Instant, downloaded: u64) {
+        self.samples.push_back((at, downloaded));
+        while let Some(&(first, _)) = self.samples.front() {
+            if at.duration_since(first) <= RATE_WINDOW || self.samples.len() <= 2 {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// Bytes per second across the window, once it spans some time.
+    pub fn bytes_per_sec(&self) -> Option<f64> {
+        let (first_at, first) = self.samples.front()?;
+        let (last_at, last) = self.samples.back()?;
+        let secs = last_at.duration_since(*first_at).as_secs_f64();
+        (secs >= 0.5).then(|| last.saturating_sub(*first) as f64 / secs)
+    }
+}
+
+/// "45 s", "3 min 20 s" or "1 h 5 min".
+pub fn format_eta(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs} s"),
+        60..3600 if secs % 60 == 0 || secs >= 600 => format!("{} min", secs / 60),
+        60..3600 => format!("{} min {} s", secs / 60, secs % 60),
+        _ => format!("{} h {} min", secs / 3600, secs / 60 % 60),
+    }
+}
+
+/// Speed and time left of a download, e.g. "4.2 MB/s, 31 s left". Empty
+/// until the speed is known; without a total only the speed is given.
+pub fn progress_detail(downloaded: u64, total: Option<u64>, bytes_per_sec: Option<f64>) -> String {
+    let Some(rate) = bytes_per_sec else {
+        return String::new();
+    };
+    let speed = format!("{:.1} MB/s", rate / (1024.0 * 1024.0));
+    match total {
+        Some(total) if rate >= 1.0 && total > downloaded => {
+            let left = ((total - downloaded) as f64 / rate).ceil() as u64;
+            format!("{speed}, {} left", format_eta(left))
+        }
+        _ => speed,
+    }
+}
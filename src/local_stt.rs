@@ -1,3 +1,4 @@
+use crate::transcript::{Segment, TranscribeOptions, Transcript};
 use rubato::{
     Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
 };
@@ -20,7 +21,17 @@ impl LocalWhisper {
         Ok(Self { ctx })
     }
 
-    pub fn transcribe(&self, wav_data: &[u8], device_sample_rate: u32) -> Result<String, String> {
+    /// Whether the loaded model understands languages other than English.
+    pub fn is_multilingual(&self) -> bool {
+        self.ctx.is_multilingual()
+    }
+
+    pub fn transcribe(
+        &self,
+        wav_data: &[u8],
+        device_sample_rate: u32,
+        options: &TranscribeOptions,
+    ) -> Result<Transcript, String> {
         // Parse WAV to f32 samples
         let cursor = Cursor::new(wav_data);
         let mut reader =
@@ -34,11 +45,25 @@ impl LocalWhisper {
             return Err("No audio samples in WAV".into());
         }
 
+        self.transcribe_samples(&samples, device_sample_rate, false, options)
+    }
+
+    /// Transcribe mono f32 samples directly (no WAV round trip).
+    ///
+    /// `partial` marks an intermediate streaming pass: it skips cross-segment
+    /// context so a growing window doesn't condition on its own earlier guesses.
+    pub fn transcribe_samples(
+        &self,
+        samples: &[f32],
+        device_sample_rate: u32,
+        partial: bool,
+        options: &TranscribeOptions,
+    ) -> Result<Transcript, String> {
         // Resample to 16kHz if needed
         let audio_16k = if device_sample_rate == WHISPER_SAMPLE_RATE {
-            samples
+            samples.to_vec()
         } else {
-            resample(&samples, device_sample_rate, WHISPER_SAMPLE_RATE)?
+            resample(samples, device_sample_rate, WHISPER_SAMPLE_RATE)?
         };
 
         // Run whisper inference
@@ -46,29 +71,75 @@ impl LocalWhisper {
             .ctx
             .create_state()
             .map_err(|e| format!("Failed to create whisper state: {e}"))?;
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        let decoding = &options.decoding;
+        let strategy = if decoding.beam_size > 1 {
+            SamplingStrategy::BeamSearch {
+                beam_size: decoding.beam_size as i32,
+                patience: -1.0,
+            }
+        } else {
+            SamplingStrategy::Greedy { best_of: 1 }
+        };
+        let mut params = FullParams::new(strategy);
         params.set_print_special(false);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
+        params.set_n_threads(decoding.threads.max(1) as i32);
+        params.set_temperature(decoding.temperature);
+        params.set_temperature_inc(decoding.temperature_inc);
+        params.set_no_context(decoding.no_context || partial);
+        params.set_suppress_blank(decoding.suppress_blank);
+        params.set_audio_ctx(decoding.audio_ctx as i32);
+        // English-only models can't detect or switch language
+        let language = if self.is_multilingual() {
+            options.language.as_deref().unwrap_or("auto")
+        } else {
+            "en"
+        };
+        params.set_language(Some(language));
+        params.set_translate(options.translate);
+        if let Some(prompt) = &options.prompt {
+            params.set_initial_prompt(prompt);
+        }
 
         state
             .full(params, &audio_16k)
             .map_err(|e| format!("Whisper inference failed: {e}"))?;
 
-        // Collect transcription text
+        // Collect transcription text and segment timings (in 10 ms units)
         let mut text = String::new();
+        let mut segments = Vec::new();
         for segment in state.as_iter() {
             if let Ok(s) = segment.to_str() {
                 text.push_str(s);
+                segments.push(Segment {
+                    start_ms: segment.start_timestamp().max(0) as u64 * 10,
+                    end_ms: segment.end_timestamp().max(0) as u64 * 10,
+                    text: s.trim().to_string(),
+                });
             }
         }
 
-        Ok(text.trim().to_string())
+        // The language whisper settled on (the fixed one, or the detected one)
+        let language = whisper_rs::get_lang_str(state.full_lang_id_from_state())
+            .map(str::to_string)
+            .or_else(|| options.language.clone());
+
+        Ok(Transcript {
+            text: text.trim().to_string(),
+            language,
+            segments,
+        })
     }
 }
 
-fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, String> {
+pub(crate) fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, String> {
+    // Integer ratios (48 kHz → 16 kHz) only need a low-pass at the kept samples
+    if from_rate > to_rate && from_rate % to_rate == 0 {
+        return Ok(decimate(input, (from_rate / to_rate) as usize));
+    }
+
     let params = SincInterpolationParameters {
         sinc_len: 256,
         f_cutoff: 0.95,
@@ -105,3 +176,52 @@ fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, Str
 
     Ok(output)
 }
+
+/// Filter taps per unit of decimation factor (73 taps for 48 kHz → 16 kHz).
+const DECIMATE_TAPS_PER_FACTOR: usize = 24;
+
+/// Downsample by an integer factor with a Blackman-windowed sinc low-pass,
+/// evaluated only at the output samples.
+pub(crate) fn decimate(input: &[f32], factor: usize) -> Vec<f32> {
+    if factor <= 1 {
+        return input.to_vec();
+    }
+
+    // Cut off a little below the new Nyquist frequency
+    let cutoff = 0.45 / factor as f64;
+    let taps = DECIMATE_TAPS_PER_FACTOR * factor + 1;
+    let mid = (taps / 2) as f64;
+    let mut kernel: Vec<f64> = (0..taps)
+        .map(|i| {
+            let x = i as f64 - mid;
+            let sinc = if x == 0.0 {
+                2.0 * cutoff
+            } else {
+                (2.0 * std::f64::consts::PI * cutoff * x).sin() / (std::f64::consts::PI * x)
+            };
+            let phase = 2.0 * std::f64::consts::PI * i as f64 / (taps - 1) as f64;
+            let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+            sinc * window
+        })
+        .collect();
+    let sum: f64 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|k| *k /= sum);
+    let kernel: Vec<f32> = kernel.into_iter().map(|k| k as f32).collect();
+
+    let half = taps / 2;
+    (0..input.len().div_ceil(factor))
+        .map(|out| {
+            let center = out * factor;
+            kernel
+                .iter()
+                .enumerate()
+                .filter_map(|(i, k)| {
+                    (center + i)
+                        .checked_sub(half)
+                        .and_then(|idx| input.get(idx))
+                        .map(|s| s * k)
+                })
+                .sum()
+        })
+        .collect()
+}
@@ -22,12 +22,19 @@
 mod log;
 mod api;
 mod audio;
+mod audio_file;
+mod audio_source;
 mod config;
 mod db;
+mod download;
 mod input;
 mod local_stt;
+mod models;
+mod preprocess;
+mod retention;
 #[cfg(test)]
 mod tests;
+mod transcript;
 mod tts;
 mod ui;
 
@@ -39,19 +46,116 @@ fn main() {
     let debug = args.iter().any(|a| a == "--debug");
     log::init(debug);
 
-    let config = Arc::new(config::Config::load());
+    // Filter out our own flags so GTK4 doesn't reject them as unknown options
+    let mut audio_source = None;
+    let mut subtitles = None;
+    let mut gtk_args = Vec::new();
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--debug" {
+            continue;
+        }
+        if let Some(spec) = arg.strip_prefix("--audio-source=") {
+            audio_source = Some(spec.to_string());
+        } else if arg == "--audio-source" {
+            audio_source = iter.next();
+        } else if let Some(path) = arg.strip_prefix("--subtitles=") {
+            subtitles = Some(path.to_string());
+        } else if arg == "--subtitles" {
+            subtitles = iter.next();
+        } else {
+            gtk_args.push(arg);
+        }
+    }
+
+    // Files are transcribed one at a time; a second one would only find the
+    // first still running
+    if gtk_args
+        .iter()
+        .skip(1)
+        .filter(|a| !a.starts_with('-'))
+        .count()
+        > 1
+    {
+        eprintln!("Only one audio file can be transcribed at a time");
+        std::process::exit(2);
+    }
+
+    let mut config = config::Config::load();
+    // `--audio-source=tone` etc. overrides AUDIO_SOURCE
+    if let Some(spec) = audio_source {
+        match config::AudioSourceSpec::parse(&spec) {
+            Ok(source) => config.audio_source = source,
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        }
+    }
+    let config = Arc::new(config);
 
     let app = gtk4::Application::builder()
         .application_id("dev.whispercrabs.app")
+        .flags(gtk4::gio::ApplicationFlags::HANDLES_OPEN)
         .build();
 
+    // `whispercrabs memo.m4a --subtitles memo.srt` — also write subtitles. The
+    // file goes to "transcribe-file" as `<file>|<subtitles>` instead of being
+    // opened, straight to the running instance if there is one.
+    let mut file_request = None;
+    if let Some(subtitles) = subtitles {
+        let Some(index) = gtk_args.iter().skip(1).position(|a| !a.starts_with('-')) else {
+            eprintln!("--subtitles needs an audio file to transcribe");
+            std::process::exit(2);
+        };
+        let file = gtk_args.remove(index + 1);
+        let file = std::path::absolute(&file).unwrap_or_else(|_| file.into());
+        // The running instance may have another working directory
+        let subtitles = std::path::absolute(&subtitles).unwrap_or_else(|_| subtitles.into());
+        let request = format!("{}|{}", file.display(), subtitles.display());
+        if let Err(e) = app.register(None::<&gtk4::gio::Cancellable>) {
+            eprintln!("Failed to register application: {e}");
+            std::process::exit(1);
+        }
+        if app.is_remote() {
+            app.activate_action("transcribe-file", Some(&request.to_variant()));
+            if let Some(connection) = app.dbus_connection() {
+                let _ = connection.flush_sync(None::<&gtk4::gio::Cancellable>);
+            }
+            return;
+        }
+        file_request = Some(request);
+    }
+
     let config_c = Arc::clone(&config);
+    let file_request = std::cell::Cell::new(file_request);
     app.connect_activate(move |app| {
         ui::build_ui(app, Arc::clone(&config_c));
+        if let Some(request) = file_request.take() {
+            app.activate_action("transcribe-file", Some(&request.to_variant()));
+        }
+    });
+
+    // `whispercrabs memo.m4a` — transcribe an audio file given on the command line.
+    // A second invocation forwards the file to the running instance.
+    app.connect_open(|app, files, _| {
+        if app.windows().is_empty() {
+            app.activate();
+        }
+        if files.len() > 1 {
+            eprintln!(
+                "Only one audio file can be transcribed at a time, ignoring {} more",
+                files.len() - 1
+            );
+        }
+        if let Some(path) = files.first().and_then(|f| f.path()) {
+            app.activate_action(
+                "transcribe-file",
+                Some(&path.to_string_lossy().to_variant()),
+            );
+        }
     });
 
-    // Filter out --debug so GTK4 doesn't reject it as unknown option
-    let gtk_args: Vec<String> = args.into_iter().filter(|a| a != "--debug").collect();
     let gtk_args_ref: Vec<&str> = gtk_args.iter().map(|s| s.as_str()).collect();
     app.run_with_args(&gtk_args_ref);
 }
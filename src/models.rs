@@ -0,0 +1,96 @@
+//! Local whisper model files on disk: which are installed, and which
+//! downloaded ones to evict when they outgrow the disk budget.
+
+use crate::config::{self, CustomModel};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A model file present on disk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstalledModel {
+    /// Preset ID, or the `local:` ID of an imported file.
+    pub id: String,
+    pub label: String,
+    pub path: PathBuf,
+    /// File size in bytes.
+    pub size: u64,
+    /// Local time it was last switched to or transcribed with, if ever.
+    pub last_used: Option<String>,
+    /// Imported from elsewhere on disk. These are never deleted, only
+    /// forgotten, and don't count against the budget.
+    pub imported: bool,
+}
+
+/// Downloaded presets in `models_dir`, then imported files that still exist.
+pub fn installed_models(
+    models_dir: &Path,
+    imported: &[CustomModel],
+    last_used: &HashMap<String, String>,
+) -> Vec<InstalledModel> {
+    let presets = config::LOCAL_MODEL_PRESETS.iter().map(|p| {
+        let label = if p.multilingual {
+            format!("{} (multilingual)", p.label)
+        } else {
+            p.label.to_string()
+        };
+        (p.id.to_string(), label, models_dir.join(p.file_name), false)
+    });
+    let imported = imported
+        .iter()
+        .map(|m| (m.id.clone(), m.label.clone(), m.path.clone(), true));
+    presets
+        .chain(imported)
+        .filter_map(|(id, label, path, imported)| {
+            let size = std::fs::metadata(&path).ok().filter(|m| m.is_file())?.len();
+            Some(InstalledModel {
+                last_used: last_used.get(&id).cloned(),
+                id,
+                label,
+                path,
+                size,
+                imported,
+            })
+        })
+        .collect()
+}
+
+/// Downloaded models to delete so the rest fit in `max_bytes`: never used
+/// ones first, then least recently used. Imported files and the IDs in
+/// `keep` are never picked. A budget of 0 is unlimited.
+pub fn lru_evictions<'a>(
+    models: &'a [InstalledModel],
+    max_bytes: u64,
+    keep: &[&str],
+) -> Vec<&'a InstalledModel> {
+    if max_bytes == 0 {
+        return Vec::new();
+    }
+    let mut total: u64 = models.iter().filter(|m| !m.imported).map(|m| m.size).sum();
+    let mut candidates: Vec<&InstalledModel> = models
+        .iter()
+        .filter(|m| !m.imported && !keep.contains(&m.id.as_str()))
+        .collect();
+    // "YYYY-MM-DD HH:MM:SS" sorts by time as text, and None sorts first
+    candidates.sort_by(|a, b| a.last_used.cmp(&b.last_used));
+    candidates
+        .into_iter()
+        .take_while(|m| {
+            let over = total > max_bytes;
+            if over {
+                total -= m.size;
+            }
+            over
+        })
+        .collect()
+}
+
+/// Human-readable file size ("75 MB", "1.6 GB").
+pub fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{mb:.0} MB")
+    }
+}
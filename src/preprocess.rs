@@ -0,0 +1,164 @@
+//! Optional audio cleanup between capture and speech-to-text.
+//!
+//! Three independent stages, applied in order: high-pass filter (desk rumble,
+//! handling noise), RNNoise noise suppression, and loudness normalization
+//! (gain for quiet mics, limited by a peak ceiling).
+
+use std::io::Cursor;
+
+use nnnoiseless::DenoiseState;
+
+/// Cutoff for the rumble filter. Speech energy starts well above this.
+pub const HIGH_PASS_HZ: f32 = 80.0;
+
+/// RMS level that normalization aims for (-20 dBFS).
+pub const TARGET_RMS: f32 = 0.1;
+
+/// Peak ceiling after normalization (-1 dBFS).
+pub const PEAK_CEILING: f32 = 0.891;
+
+/// Largest boost normalization will apply (+30 dB).
+pub const MAX_GAIN: f32 = 31.6;
+
+/// Below this RMS the input is treated as silence and left untouched.
+const SILENCE_RMS: f32 = 0.0005;
+
+/// RNNoise operates on 48 kHz audio.
+const DENOISE_SAMPLE_RATE: u32 = 48000;
+
+/// Which preprocessing stages are enabled.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PreprocessOptions {
+    pub high_pass: bool,
+    pub denoise: bool,
+    pub normalize: bool,
+}
+
+impl PreprocessOptions {
+    pub fn any(&self) -> bool {
+        self.high_pass || self.denoise || self.normalize
+    }
+}
+
+/// Run the enabled stages over mono samples in place.
+pub fn process(samples: &mut Vec<f32>, sample_rate: u32, opts: PreprocessOptions) {
+    if opts.high_pass {
+        high_pass(samples, sample_rate, HIGH_PASS_HZ);
+    }
+    if opts.denoise {
+        match denoise(samples, sample_rate) {
+            Ok(clean) => *samples = clean,
+            Err(e) => eprintln!("Denoise failed, using raw audio: {e}"),
+        }
+    }
+    if opts.normalize {
+        normalize(samples);
+    }
+}
+
+/// Decode a mono 16-bit WAV, run the enabled stages, and re-encode it.
+pub fn process_wav(wav_data: &[u8], opts: PreprocessOptions) -> Result<Vec<u8>, String> {
+    if !opts.any() {
+        return Ok(wav_data.to_vec());
+    }
+    let mut reader = hound::WavReader::new(Cursor::new(wav_data))
+        .map_err(|e| format!("WAV parse error: {e}"))?;
+    let spec = reader.spec();
+    if spec.channels != 1 || spec.bits_per_sample != 16 {
+        return Err("Preprocessing expects mono 16-bit WAV".into());
+    }
+    let mut samples: Vec<f32> = reader
+        .samples::<i16>()
+        .map(|s| s.unwrap_or(0) as f32 / i16::MAX as f32)
+        .collect();
+
+    process(&mut samples, spec.sample_rate, opts);
+
+    let mut buf = Cursor::new(Vec::new());
+    let mut writer =
+        hound::WavWriter::new(&mut buf, spec).map_err(|e| format!("WAV write error: {e}"))?;
+    for &sample in &samples {
+        let s = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        writer
+            .write_sample(s)
+            .map_err(|e| format!("WAV sample error: {e}"))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("WAV finalize error: {e}"))?;
+    Ok(buf.into_inner())
+}
+
+/// Second-order Butterworth high-pass (RBJ biquad).
+pub fn high_pass(samples: &mut [f32], sample_rate: u32, cutoff_hz: f32) {
+    let w0 = std::f32::consts::TAU * cutoff_hz / sample_rate as f32;
+    let (sin_w0, cos_w0) = w0.sin_cos();
+    let alpha = sin_w0 / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+
+    let a0 = 1.0 + alpha;
+    let b0 = (1.0 + cos_w0) / 2.0 / a0;
+    let b1 = -(1.0 + cos_w0) / a0;
+    let b2 = b0;
+    let a1 = -2.0 * cos_w0 / a0;
+    let a2 = (1.0 - alpha) / a0;
+
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for s in samples.iter_mut() {
+        let x0 = *s;
+        let y0 = b0 * x0 + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+        x2 = x1;
+        x1 = x0;
+        y2 = y1;
+        y1 = y0;
+        *s = y0;
+    }
+}
+
+/// RNNoise noise suppression. Input at other rates is resampled to 48 kHz and back.
+pub fn denoise(samples: &[f32], sample_rate: u32) -> Result<Vec<f32>, String> {
+    let input = if sample_rate == DENOISE_SAMPLE_RATE {
+        samples.to_vec()
+    } else {
+        crate::local_stt::resample(samples, sample_rate, DENOISE_SAMPLE_RATE)?
+    };
+
+    // RNNoise expects i16-scaled floats
+    let frame = DenoiseState::FRAME_SIZE;
+    let mut state = DenoiseState::new();
+    let mut output = Vec::with_capacity(input.len() + frame);
+    let mut in_frame = vec![0.0f32; frame];
+    let mut out_frame = vec![0.0f32; frame];
+    for chunk in input.chunks(frame) {
+        in_frame.fill(0.0);
+        for (dst, &src) in in_frame.iter_mut().zip(chunk) {
+            *dst = src * i16::MAX as f32;
+        }
+        state.process_frame(&mut out_frame, &in_frame);
+        output.extend(out_frame[..chunk.len()].iter().map(|s| s / i16::MAX as f32));
+    }
+
+    let mut output = if sample_rate == DENOISE_SAMPLE_RATE {
+        output
+    } else {
+        crate::local_stt::resample(&output, DENOISE_SAMPLE_RATE, sample_rate)?
+    };
+    output.resize(samples.len(), 0.0);
+    Ok(output)
+}
+
+/// Bring the RMS level towards [`TARGET_RMS`] without pushing peaks past
+/// [`PEAK_CEILING`] or boosting by more than [`MAX_GAIN`].
+pub fn normalize(samples: &mut [f32]) {
+    if samples.is_empty() {
+        return;
+    }
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    if rms < SILENCE_RMS {
+        return;
+    }
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    let gain = (TARGET_RMS / rms).min(PEAK_CEILING / peak).min(MAX_GAIN);
+    for s in samples.iter_mut() {
+        *s *= gain;
+    }
+}
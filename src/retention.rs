@@ -0,0 +1,225 @@
+//! Compact storage of recordings kept alongside history entries.
+//!
+//! Recordings are resampled to 16 kHz mono and stored as IMA ADPCM WAV
+//! (4 bits per sample, ~8 KB/s). That is what whisper consumes anyway and
+//! plays back in any audio player.
+
+use std::io::Cursor;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Sample rate of retained recordings.
+pub const RETAINED_SAMPLE_RATE: u32 = 16000;
+
+/// Bytes per ADPCM block (mono): 4-byte header plus 252 bytes of nibbles.
+const BLOCK_ALIGN: usize = 256;
+
+/// Samples per block: the header sample plus two per remaining byte.
+const SAMPLES_PER_BLOCK: usize = (BLOCK_ALIGN - 4) * 2 + 1;
+
+const WAVE_FORMAT_IMA_ADPCM: u16 = 0x0011;
+
+const INDEX_TABLE: [i32; 16] = [-1, -1, -1, -1, 2, 4, 6, 8, -1, -1, -1, -1, 2, 4, 6, 8];
+
+const STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449,
+    494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272,
+    2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493,
+    10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+/// Compress a mono 16-bit WAV (any sample rate) for retention.
+pub fn compress_wav(wav_data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut reader = hound::WavReader::new(Cursor::new(wav_data))
+        .map_err(|e| format!("WAV parse error: {e}"))?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = reader
+        .samples::<i16>()
+        .map(|s| s.unwrap_or(0) as f32 / i16::MAX as f32)
+        .collect();
+    let samples = crate::audio::downmix(&samples, spec.channels);
+    let samples = if spec.sample_rate == RETAINED_SAMPLE_RATE {
+        samples
+    } else {
+        crate::local_stt::resample(&samples, spec.sample_rate, RETAINED_SAMPLE_RATE)?
+    };
+    let pcm: Vec<i16> = samples
+        .iter()
+        .map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect();
+    Ok(encode_adpcm_wav(&pcm, RETAINED_SAMPLE_RATE))
+}
+
+/// Decode a retained recording back to 16-bit samples at [`RETAINED_SAMPLE_RATE`].
+pub fn decompress(data: &[u8]) -> Result<Vec<i16>, String> {
+    let mss = MediaSourceStream::new(Box::new(Cursor::new(data.to_vec())), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension("wav");
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Stored audio unreadable: {e}"))?;
+    let mut format = probed.format;
+    let track = format.default_track().ok_or("Stored audio has no track")?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Stored audio codec error: {e}"))?;
+
+    let mut samples = Vec::new();
+    while let Ok(packet) = format.next_packet() {
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = decoder
+            .decode(&packet)
+            .map_err(|e| format!("Stored audio decode error: {e}"))?;
+        let mut buf = SampleBuffer::<i16>::new(decoded.capacity() as u64, *decoded.spec());
+        buf.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buf.samples());
+    }
+    // The last block is padded with silence; the fact chunk has the real length
+    if let Some(total) = fact_sample_count(data) {
+        samples.truncate(total);
+    }
+    Ok(samples)
+}
+
+/// Decode a retained recording into a 16-bit PCM WAV for re-transcription.
+pub fn decompress_to_wav(data: &[u8]) -> Result<Vec<u8>, String> {
+    let samples = decompress(data)?;
+    let mut buf = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: RETAINED_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer =
+        hound::WavWriter::new(&mut buf, spec).map_err(|e| format!("WAV write error: {e}"))?;
+    for &s in &samples {
+        writer
+            .write_sample(s)
+            .map_err(|e| format!("WAV sample error: {e}"))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("WAV finalize error: {e}"))?;
+    Ok(buf.into_inner())
+}
+
+/// Sample count from the WAV `fact` chunk, if present.
+fn fact_sample_count(data: &[u8]) -> Option<usize> {
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        if id == b"fact" && size >= 4 {
+            let count = data.get(pos + 8..pos + 12)?;
+            return Some(u32::from_le_bytes(count.try_into().ok()?) as usize);
+        }
+        // Chunks are padded to an even size
+        pos += 8 + size + (size & 1);
+    }
+    None
+}
+
+/// Encode mono 16-bit samples as an IMA ADPCM WAV file.
+fn encode_adpcm_wav(pcm: &[i16], sample_rate: u32) -> Vec<u8> {
+    let blocks = pcm.len().div_ceil(SAMPLES_PER_BLOCK);
+    let data_len = blocks * BLOCK_ALIGN;
+    let byte_rate = sample_rate as usize * BLOCK_ALIGN / SAMPLES_PER_BLOCK;
+
+    let mut out = Vec::with_capacity(60 + data_len);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&((52 + data_len) as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&20u32.to_le_bytes());
+    out.extend_from_slice(&WAVE_FORMAT_IMA_ADPCM.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // channels
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(byte_rate as u32).to_le_bytes());
+    out.extend_from_slice(&(BLOCK_ALIGN as u16).to_le_bytes());
+    out.extend_from_slice(&4u16.to_le_bytes()); // bits per sample
+    out.extend_from_slice(&2u16.to_le_bytes()); // extra format bytes
+    out.extend_from_slice(&(SAMPLES_PER_BLOCK as u16).to_le_bytes());
+
+    out.extend_from_slice(b"fact");
+    out.extend_from_slice(&4u32.to_le_bytes());
+    out.extend_from_slice(&(pcm.len() as u32).to_le_bytes());
+
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(data_len as u32).to_le_bytes());
+
+    let mut index = 0i32;
+    for block in pcm.chunks(SAMPLES_PER_BLOCK) {
+        let mut predictor = block[0] as i32;
+        out.extend_from_slice(&block[0].to_le_bytes());
+        out.push(index as u8);
+        out.push(0);
+
+        // Pad the final block with silence so every block is full size
+        let rest = block[1..]
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(0))
+            .take(SAMPLES_PER_BLOCK - 1)
+            .collect::<Vec<i16>>();
+        for pair in rest.chunks(2) {
+            let lo = encode_nibble(&mut predictor, &mut index, pair[0]);
+            let hi = encode_nibble(&mut predictor, &mut index, pair[1]);
+            out.push(lo | (hi << 4));
+        }
+    }
+    out
+}
+
+/// Quantize one sample against the running predictor (standard IMA step).
+fn encode_nibble(predictor: &mut i32, index: &mut i32, sample: i16) -> u8 {
+    let mut step = STEP_TABLE[*index as usize];
+    let mut diff = sample as i32 - *predictor;
+    let mut nibble = 0u8;
+    if diff < 0 {
+        nibble = 8;
+        diff = -diff;
+    }
+
+    let mut delta = step >> 3;
+    if diff >= step {
+        nibble |= 4;
+        diff -= step;
+        delta += step;
+    }
+    step >>= 1;
+    if diff >= step {
+        nibble |= 2;
+        diff -= step;
+        delta += step;
+    }
+    step >>= 1;
+    if diff >= step {
+        nibble |= 1;
+        delta += step;
+    }
+
+    if nibble & 8 != 0 {
+        *predictor -= delta;
+    } else {
+        *predictor += delta;
+    }
+    *predictor = (*predictor).clamp(i16::MIN as i32, i16::MAX as i32);
+    *index = (*index + INDEX_TABLE[nibble as usize]).clamp(0, 88);
+    nibble
+}
@@ -1,4 +1,5 @@
 use crate::api;
+use crate::transcript::TranscribeOptions;
 
 #[test]
 fn transcribe_url_strips_trailing_slash() {
@@ -16,9 +17,51 @@ fn transcribe_url_no_trailing_slash() {
     assert_eq!(url, "https://api.groq.com/openai/v1/audio/transcriptions");
 }
 
+#[test]
+fn endpoint_url_picks_transcriptions_or_translations() {
+    assert_eq!(
+        api::endpoint_url("https://api.groq.com/openai/v1/", false),
+        "https://api.groq.com/openai/v1/audio/transcriptions"
+    );
+    assert_eq!(
+        api::endpoint_url("https://api.groq.com/openai/v1", true),
+        "https://api.groq.com/openai/v1/audio/translations"
+    );
+}
+
+#[test]
+fn request_model_swaps_turbo_for_translations() {
+    assert_eq!(
+        api::request_model("whisper-large-v3-turbo", true),
+        "whisper-large-v3"
+    );
+    assert_eq!(
+        api::request_model("whisper-large-v3-turbo", false),
+        "whisper-large-v3-turbo"
+    );
+    assert_eq!(api::request_model("whisper-1", true), "whisper-1");
+}
+
+#[tokio::test]
+async fn translate_rejects_invalid_url() {
+    let options = TranscribeOptions {
+        translate: true,
+        ..Default::default()
+    };
+    let result = api::transcribe("ftp://evil.com", "key", "model", vec![1], &options).await;
+    assert!(result.unwrap_err().contains("only http:// and https://"));
+}
+
 #[tokio::test]
 async fn transcribe_rejects_invalid_url() {
-    let result = api::transcribe("http://127.0.0.1:1", "fake-key", "model", vec![0u8; 44]).await;
+    let result = api::transcribe(
+        "http://127.0.0.1:1",
+        "fake-key",
+        "model",
+        vec![0u8; 44],
+        &TranscribeOptions::default(),
+    )
+    .await;
     assert!(result.is_err());
     let err = result.unwrap_err();
     assert!(
@@ -30,20 +73,96 @@ async fn transcribe_rejects_invalid_url() {
 #[tokio::test]
 async fn transcribe_rejects_empty_wav() {
     // Even with a valid-looking URL, an empty body should fail at the multipart level or server
-    let result = api::transcribe("http://127.0.0.1:1", "key", "model", vec![]).await;
+    let result = api::transcribe(
+        "http://127.0.0.1:1",
+        "key",
+        "model",
+        vec![],
+        &TranscribeOptions::default(),
+    )
+    .await;
     assert!(result.is_err());
 }
 
 #[tokio::test]
 async fn transcribe_rejects_file_url() {
-    let result = api::transcribe("file:///etc/passwd", "key", "model", vec![1]).await;
+    let result = api::transcribe(
+        "file:///etc/passwd",
+        "key",
+        "model",
+        vec![1],
+        &TranscribeOptions::default(),
+    )
+    .await;
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("only http:// and https://"));
 }
 
 #[tokio::test]
 async fn transcribe_rejects_ftp_url() {
-    let result = api::transcribe("ftp://evil.com", "key", "model", vec![1]).await;
+    let result = api::transcribe(
+        "ftp://evil.com",
+        "key",
+        "model",
+        vec![1],
+        &TranscribeOptions::default(),
+    )
+    .await;
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("only http:// and https://"));
 }
+
+#[test]
+fn parse_response_reads_verbose_json_segments() {
+    let json = serde_json::json!({
+        "text": " Olá a todos. Bem-vindos.",
+        "language": "portuguese",
+        "segments": [
+            {"id": 0, "start": 0.0, "end": 1.52, "text": " Olá a todos."},
+            {"id": 1, "start": 1.52, "end": 3.0, "text": " Bem-vindos."}
+        ]
+    });
+    let transcript = api::parse_response(&json, &TranscribeOptions::default()).unwrap();
+    assert_eq!(transcript.text, "Olá a todos. Bem-vindos.");
+    assert_eq!(transcript.language.as_deref(), Some("pt"));
+    assert_eq!(transcript.segments.len(), 2);
+    assert_eq!(transcript.segments[0].start_ms, 0);
+    assert_eq!(transcript.segments[0].end_ms, 1520);
+    assert_eq!(transcript.segments[1].text, "Bem-vindos.");
+}
+
+#[test]
+fn parse_response_accepts_plain_json() {
+    let json = serde_json::json!({"text": "hello"});
+    let transcript = api::parse_response(&json, &TranscribeOptions::default()).unwrap();
+    assert_eq!(transcript.text, "hello");
+    assert!(transcript.segments.is_empty());
+    assert_eq!(transcript.language, None);
+    assert!(api::parse_response(&serde_json::json!({}), &TranscribeOptions::default()).is_err());
+}
+
+#[test]
+fn parse_response_prefers_fixed_language() {
+    let json = serde_json::json!({"text": "hi", "language": "english"});
+    let fixed = TranscribeOptions {
+        language: Some("de".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        api::parse_response(&json, &fixed)
+            .unwrap()
+            .language
+            .as_deref(),
+        Some("de")
+    );
+
+    // A translation reports its English output, not the spoken language
+    let translate = TranscribeOptions {
+        translate: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        api::parse_response(&json, &translate).unwrap().language,
+        None
+    );
+}
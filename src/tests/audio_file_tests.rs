@@ -0,0 +1,115 @@
+use crate::audio_file::{
+    FILE_SAMPLE_RATE, decode_file, file_to_wav, is_supported, parse_file_request,
+};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Helper: write a 16-bit WAV file from interleaved f32 samples
+fn write_wav(path: &Path, samples: &[f32], sample_rate: u32, channels: u16) {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for &s in samples {
+        writer
+            .write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+#[test]
+fn supported_extensions() {
+    assert!(is_supported(Path::new("memo.wav")));
+    assert!(is_supported(Path::new("/tmp/meeting.FLAC")));
+    assert!(is_supported(Path::new("voice.mp3")));
+    assert!(is_supported(Path::new("voice.ogg")));
+    assert!(is_supported(Path::new("Recording 12.m4a")));
+    assert!(!is_supported(Path::new("voice.opus")));
+    assert!(!is_supported(Path::new("notes.txt")));
+    assert!(!is_supported(Path::new("no_extension")));
+}
+
+#[test]
+fn file_request_splits_off_subtitle_path() {
+    assert_eq!(
+        parse_file_request("/nonexistent/talk.m4a"),
+        (PathBuf::from("/nonexistent/talk.m4a"), None)
+    );
+    assert_eq!(
+        parse_file_request("/nonexistent/talk.m4a|/tmp/talk.srt"),
+        (
+            PathBuf::from("/nonexistent/talk.m4a"),
+            Some(PathBuf::from("/tmp/talk.srt"))
+        )
+    );
+
+    // An existing file whose name contains the separator is taken whole
+    let dir = tempfile::tempdir().unwrap();
+    let odd = dir.path().join("a|b.wav");
+    std::fs::write(&odd, b"").unwrap();
+    assert_eq!(
+        parse_file_request(odd.to_str().unwrap()),
+        (odd.clone(), None)
+    );
+}
+
+#[test]
+fn decode_wav_file_to_mono() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stereo.wav");
+    // Left at 0.5, right at -0.5: averages to silence
+    let samples: Vec<f32> = (0..4410).flat_map(|_| [0.5, -0.5]).collect();
+    write_wav(&path, &samples, 44100, 2);
+
+    let (mono, rate) = decode_file(&path).unwrap();
+    assert_eq!(rate, 44100);
+    assert_eq!(mono.len(), 4410);
+    assert!(mono.iter().all(|s| s.abs() < 1e-3));
+}
+
+#[test]
+fn file_to_wav_resamples_to_16k() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memo.wav");
+    let samples: Vec<f32> = (0..48000)
+        .map(|i| 0.3 * (i as f32 * 440.0 * std::f32::consts::TAU / 48000.0).sin())
+        .collect();
+    write_wav(&path, &samples, 48000, 1);
+
+    let wav = file_to_wav(&path).unwrap();
+    let reader = hound::WavReader::new(Cursor::new(&wav)).unwrap();
+    assert_eq!(reader.spec().channels, 1);
+    assert_eq!(reader.spec().sample_rate, FILE_SAMPLE_RATE);
+    // One second of audio, give or take the resampler's block padding
+    let len = reader.len() as i64;
+    assert!((len - 16000).abs() < 1100, "got {len} samples");
+}
+
+#[test]
+fn file_to_wav_keeps_16k_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("already16k.wav");
+    write_wav(&path, &[0.1; 8000], 16000, 1);
+
+    let wav = file_to_wav(&path).unwrap();
+    let reader = hound::WavReader::new(Cursor::new(&wav)).unwrap();
+    assert_eq!(reader.len(), 8000);
+}
+
+#[test]
+fn decode_rejects_non_audio() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("fake.mp3");
+    std::fs::write(&path, b"definitely not audio").unwrap();
+    assert!(decode_file(&path).is_err());
+}
+
+#[test]
+fn decode_missing_file() {
+    let err = decode_file(Path::new("/nonexistent/memo.wav")).unwrap_err();
+    assert!(err.contains("Cannot open"));
+}
@@ -0,0 +1,200 @@
+use crate::audio::Recorder;
+use crate::audio_source::{
+    AudioSource, FileSource, TONE_SAMPLE_RATE, ToneSource, decode_s16le, pick_capture_rate,
+    read_pcm_file,
+};
+use crate::config::{DEFAULT_RAW_PCM, RawPcmFormat};
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Helper: write a 16-bit WAV file from interleaved f32 samples
+fn write_wav(path: &Path, samples: &[f32], sample_rate: u32, channels: u16) {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for &s in samples {
+        writer
+            .write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+/// Helper: poll until `done` or the timeout passes
+fn wait_for(timeout: Duration, done: impl Fn() -> bool) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if done() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    done()
+}
+
+#[test]
+fn decode_s16le_scales_and_drops_odd_byte() {
+    let bytes = [0x00, 0x00, 0xff, 0x7f, 0x01, 0x80, 0x42];
+    let samples = decode_s16le(&bytes);
+    assert_eq!(samples.len(), 3);
+    assert_eq!(samples[0], 0.0);
+    assert!((samples[1] - 1.0).abs() < 1e-6);
+    assert!((samples[2] + 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn read_pcm_file_without_header_uses_raw_format() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audio.raw");
+    std::fs::write(&path, [0u8; 3200]).unwrap();
+    let raw = RawPcmFormat {
+        sample_rate: 8000,
+        channels: 2,
+    };
+    let (samples, rate, channels) = read_pcm_file(&path, raw).unwrap();
+    assert_eq!((samples.len(), rate, channels), (1600, 8000, 2));
+}
+
+#[test]
+fn read_pcm_file_reads_float_wav() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("float.wav");
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 22050,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for s in [0.25f32, -0.5, 0.75] {
+        writer.write_sample(s).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let (samples, rate, channels) = read_pcm_file(&path, DEFAULT_RAW_PCM).unwrap();
+    assert_eq!(samples, vec![0.25, -0.5, 0.75]);
+    assert_eq!((rate, channels), (22050, 1));
+}
+
+#[test]
+fn file_source_probes_wav_format() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("stereo.wav");
+    write_wav(&path, &[0.0; 200], 44100, 2);
+    let source = FileSource::new(path, DEFAULT_RAW_PCM);
+    assert_eq!(source.probe(), Some((44100, 2)));
+
+    let missing = FileSource::new("/nonexistent/audio.wav".into(), DEFAULT_RAW_PCM);
+    assert_eq!(missing.probe(), None);
+}
+
+#[test]
+fn file_source_delivers_whole_file_then_ends() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("short.wav");
+    let original: Vec<f32> = (0..1600).map(|i| (i % 100) as f32 / 200.0).collect();
+    write_wav(&path, &original, 16000, 1);
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&received);
+    let stream = FileSource::new(path, DEFAULT_RAW_PCM)
+        .open(Box::new(move |data: &[f32]| {
+            sink.lock().unwrap().extend_from_slice(data)
+        }))
+        .unwrap();
+    assert_eq!(stream.format(), (16000, 1));
+
+    // Nothing arrives before play
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(received.lock().unwrap().is_empty());
+
+    stream.play().unwrap();
+    assert!(wait_for(Duration::from_secs(2), || stream.ended()));
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), original.len());
+    assert!((received[150] - original[150]).abs() < 1e-3);
+}
+
+#[test]
+fn tone_source_generates_sine() {
+    let source = ToneSource::new(1000.0);
+    assert_eq!(source.probe(), Some((TONE_SAMPLE_RATE, 1)));
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&received);
+    let stream = source
+        .open(Box::new(move |data: &[f32]| {
+            sink.lock().unwrap().extend_from_slice(data)
+        }))
+        .unwrap();
+    stream.play().unwrap();
+    assert!(wait_for(Duration::from_secs(2), || received
+        .lock()
+        .unwrap()
+        .len()
+        >= 1600));
+    drop(stream);
+    assert!(!received.lock().unwrap().is_empty());
+
+    let samples = received.lock().unwrap();
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    assert!(peak > 0.2 && peak <= 0.31, "peak {peak}");
+}
+
+#[test]
+fn recorder_records_from_tone_without_sound_card() {
+    let mut recorder = Recorder::with_source(Box::new(ToneSource::new(440.0)));
+    assert!(recorder.input_available());
+    recorder.start().unwrap();
+    assert!(wait_for(Duration::from_secs(2), || recorder.elapsed_secs() >= 0.2));
+    let wav = recorder.stop().unwrap();
+
+    let reader = hound::WavReader::new(BufReader::new(wav)).unwrap();
+    assert_eq!(reader.spec().sample_rate, TONE_SAMPLE_RATE);
+    assert_eq!(reader.spec().channels, 1);
+    assert!(reader.len() >= 3200);
+}
+
+#[test]
+fn recorder_reports_end_of_file_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memo.wav");
+    write_wav(&path, &[0.2; 1600], 16000, 1);
+
+    let mut recorder = Recorder::with_source(Box::new(FileSource::new(path, DEFAULT_RAW_PCM)));
+    recorder.start().unwrap();
+    assert!(!recorder.input_ended());
+    assert!(wait_for(Duration::from_secs(2), || recorder.input_ended()));
+
+    let wav = recorder.stop().unwrap();
+    let reader = hound::WavReader::new(BufReader::new(wav)).unwrap();
+    assert_eq!(reader.len(), 1600);
+}
+
+#[test]
+fn capture_rate_prefers_16k() {
+    // Typical USB mic: discrete 16k/44.1k/48k
+    let ranges = [(16000, 16000), (44100, 44100), (48000, 48000)];
+    assert_eq!(pick_capture_rate(&ranges, 48000), Some(16000));
+    // Continuous range covering 16 kHz
+    assert_eq!(pick_capture_rate(&[(8000, 192000)], 44100), Some(16000));
+}
+
+#[test]
+fn capture_rate_falls_back_to_integer_multiple() {
+    assert_eq!(pick_capture_rate(&[(48000, 48000)], 48000), Some(48000));
+    assert_eq!(
+        pick_capture_rate(&[(32000, 32000), (48000, 48000)], 48000),
+        Some(32000)
+    );
+    // Never go above the default rate just to hit a multiple
+    assert_eq!(pick_capture_rate(&[(96000, 96000)], 44100), None);
+    // 44.1 kHz-only devices keep their default
+    assert_eq!(pick_capture_rate(&[(44100, 44100)], 44100), None);
+}
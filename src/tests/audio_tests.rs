@@ -1,3 +1,8 @@
+use crate::audio::{
+    CaptureBuffer, CaptureSample, InputLevel, PreRollBuffer, SILENCE_FLOOR_RMS, SilenceDetector,
+    VAD_RMS_THRESHOLD, format_channel_selection, meter_fraction, mix_channels, mono_tail,
+    parse_channel_selection, read_wav, samples_to_f32, wav_file,
+};
 use std::io::Cursor;
 
 /// Helper: build a valid WAV buffer from f32 samples at given sample rate
@@ -127,3 +132,327 @@ fn u16_to_f32_conversion() {
     assert!((floats[1] - 1.0).abs() < 0.001);
     assert!(floats[2].abs() < 0.01); // midpoint ~0
 }
+
+/// Helper: a 440 Hz sine at the given amplitude
+fn tone(sample_rate: u32, ms: u32, amplitude: f32) -> Vec<f32> {
+    let n = (sample_rate * ms / 1000) as usize;
+    (0..n)
+        .map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            (t * 440.0 * std::f32::consts::TAU).sin() * amplitude
+        })
+        .collect()
+}
+
+#[test]
+fn silence_detector_fires_after_trailing_silence() {
+    let mut vad = SilenceDetector::new(16000, VAD_RMS_THRESHOLD, 500);
+    assert!(!vad.feed(&tone(16000, 1000, 0.5)));
+    assert!(!vad.feed(&vec![0.0; 16000 * 300 / 1000]));
+    assert!(vad.feed(&vec![0.0; 16000 * 300 / 1000]));
+}
+
+#[test]
+fn silence_detector_waits_for_speech() {
+    // Leading silence must not stop a recording before the user speaks
+    let mut vad = SilenceDetector::new(16000, VAD_RMS_THRESHOLD, 500);
+    assert!(!vad.feed(&vec![0.0; 16000 * 3]));
+}
+
+#[test]
+fn silence_detector_resets_on_speech() {
+    let mut vad = SilenceDetector::new(16000, VAD_RMS_THRESHOLD, 500);
+    vad.feed(&tone(16000, 500, 0.5));
+    assert!(!vad.feed(&vec![0.0; 16000 * 400 / 1000]));
+    assert!(!vad.feed(&tone(16000, 200, 0.5)));
+    assert!(!vad.feed(&vec![0.0; 16000 * 400 / 1000]));
+}
+
+#[test]
+fn silence_detector_ignores_low_noise_floor() {
+    let mut vad = SilenceDetector::new(16000, VAD_RMS_THRESHOLD, 500);
+    vad.feed(&tone(16000, 500, 0.5));
+    assert!(vad.feed(&tone(16000, 600, 0.002)));
+}
+
+#[test]
+fn mono_tail_keeps_most_recent_window() {
+    // 3 seconds of stereo at 10 Hz: L = frame index, R = -frame index
+    let samples: Vec<f32> = (0..30).flat_map(|i| [i as f32, -(i as f32)]).collect();
+    let mut buffer = CaptureBuffer::new(2, usize::MAX, 0);
+    buffer.push(&samples);
+    let buffer = std::sync::Mutex::new(buffer);
+
+    let tail = mono_tail(&buffer, 2, &[], 10, 1);
+    assert_eq!(tail.len(), 10);
+    // Frame-aligned: every frame averages to zero
+    assert!(tail.iter().all(|s| s.abs() < f32::EPSILON));
+}
+
+#[test]
+fn mono_tail_returns_everything_when_short() {
+    let mut buffer = CaptureBuffer::new(1, usize::MAX, 0);
+    buffer.push(&[0.25f32; 5]);
+    let buffer = std::sync::Mutex::new(buffer);
+    let tail = mono_tail(&buffer, 1, &[], 16000, 30);
+    assert_eq!(tail, vec![0.25f32; 5]);
+}
+
+#[test]
+fn input_level_tracks_rms_and_peak() {
+    let level = InputLevel::default();
+    assert_eq!(level.get(), (0.0, 0.0));
+
+    for _ in 0..50 {
+        level.update(&tone(16000, 30, 0.5));
+    }
+    let (rms, peak) = level.get();
+    // Sine RMS = amplitude / sqrt(2)
+    assert!((rms - 0.5 / 2f32.sqrt()).abs() < 0.01, "rms was {rms}");
+    assert!((peak - 0.5).abs() < 0.01, "peak was {peak}");
+
+    level.reset();
+    assert_eq!(level.get(), (0.0, 0.0));
+}
+
+#[test]
+fn input_level_peak_decays_on_silence() {
+    let level = InputLevel::default();
+    level.update(&[0.9, -0.9]);
+    for _ in 0..200 {
+        level.update(&[0.0; 480]);
+    }
+    let (rms, peak) = level.get();
+    assert!(rms <= SILENCE_FLOOR_RMS);
+    assert!(peak <= SILENCE_FLOOR_RMS);
+}
+
+#[test]
+fn meter_fraction_maps_db_scale() {
+    assert_eq!(meter_fraction(0.0), 0.0);
+    assert_eq!(meter_fraction(SILENCE_FLOOR_RMS), 0.0);
+    assert!((meter_fraction(1.0) - 1.0).abs() < f32::EPSILON);
+    // -20 dBFS sits two thirds of the way up
+    assert!((meter_fraction(0.1) - 2.0 / 3.0).abs() < 0.001);
+}
+
+#[test]
+fn capture_buffer_spills_past_memory_cap() {
+    // Stereo, cap at 100 samples, keep the last 20 in memory
+    let mut buffer = CaptureBuffer::new(2, 100, 20);
+    let samples: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+    for chunk in samples.chunks(30) {
+        buffer.push(chunk);
+        assert!(buffer.recent().len() <= 100 + 30);
+    }
+    assert_eq!(buffer.len(), 1000);
+    assert!(buffer.spilled() > 0);
+    assert_eq!(buffer.spilled() % 2, 0, "spill must stay frame-aligned");
+
+    // Reading back yields the whole recording in order
+    let mut out = Vec::new();
+    buffer
+        .for_each_chunk(|chunk| {
+            out.extend_from_slice(chunk);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(out, samples);
+}
+
+#[test]
+fn capture_buffer_keeps_recent_tail_after_spill() {
+    let mut buffer = CaptureBuffer::new(1, 50, 10);
+    let samples: Vec<f32> = (0..60).map(|i| i as f32).collect();
+    buffer.push(&samples);
+    assert_eq!(buffer.recent(), &samples[50..]);
+    assert_eq!(buffer.spilled(), 50);
+}
+
+#[test]
+fn capture_buffer_keeps_at_most_half_the_cap() {
+    // Asking to keep more than the cap still frees half of it per spill
+    let mut buffer = CaptureBuffer::new(2, 100, 1000);
+    let samples: Vec<f32> = (0..150).map(|i| i as f32).collect();
+    buffer.push(&samples);
+    assert_eq!(buffer.recent(), &samples[100..]);
+    assert_eq!(buffer.spilled(), 100);
+    buffer.push(&samples[..40]);
+    assert_eq!(buffer.spilled(), 100);
+    buffer.push(&samples[..20]);
+    assert_eq!(buffer.recent().len(), 50);
+    assert_eq!(buffer.len(), 210);
+}
+
+#[test]
+fn capture_buffer_spills_repeatedly_and_reads_back_in_order() {
+    let mut buffer = CaptureBuffer::new(1, 40, 10);
+    let samples: Vec<f32> = (0..500).map(|i| i as f32).collect();
+    let mut out = Vec::new();
+    // Reading finishes the pending writes; spilling resumes afterwards
+    for half in samples.chunks(250) {
+        for chunk in half.chunks(7) {
+            buffer.push(chunk);
+        }
+        out.clear();
+        buffer
+            .for_each_chunk(|chunk| {
+                out.extend_from_slice(chunk);
+                Ok(())
+            })
+            .unwrap();
+    }
+    assert_eq!(out, samples);
+}
+
+#[test]
+fn wav_file_reads_back_the_same_bytes() {
+    let mut file = wav_file(b"RIFF....WAVE").unwrap();
+    assert_eq!(read_wav(&mut file).unwrap(), b"RIFF....WAVE");
+    // Reading again starts over
+    assert_eq!(read_wav(&mut file).unwrap(), b"RIFF....WAVE");
+}
+
+#[test]
+fn capture_buffer_in_memory_only_when_small() {
+    let mut buffer = CaptureBuffer::new(1, 1000, 100);
+    buffer.push(&[0.5; 200]);
+    assert_eq!(buffer.spilled(), 0);
+    assert_eq!(buffer.recent().len(), 200);
+    assert!(!buffer.is_empty());
+}
+
+#[test]
+fn pre_roll_keeps_only_latest_frames() {
+    let mut ring = PreRollBuffer::new(2, 3);
+    let samples: Vec<f32> = (0..10).map(|i| i as f32).collect();
+    ring.push(&samples);
+    // Three stereo frames survive, oldest first and frame-aligned
+    assert_eq!(ring.len(), 6);
+    assert_eq!(ring.take(), vec![4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+    assert!(ring.is_empty());
+}
+
+#[test]
+fn pre_roll_accumulates_below_capacity() {
+    let mut ring = PreRollBuffer::new(1, 100);
+    ring.push(&[0.1; 30]);
+    ring.push(&[0.2; 30]);
+    let taken = ring.take();
+    assert_eq!(taken.len(), 60);
+    assert_eq!(taken[0], 0.1);
+    assert_eq!(taken[59], 0.2);
+}
+
+#[test]
+fn pre_roll_disabled_holds_nothing() {
+    let mut ring = PreRollBuffer::new(1, 0);
+    ring.push(&[0.5; 100]);
+    assert!(ring.is_empty());
+}
+
+#[test]
+fn mix_channels_picks_single_channel() {
+    // Four channels: only channel 3 carries signal
+    let frames: Vec<f32> = (0..4).flat_map(|i| [0.0, 0.0, i as f32, 0.0]).collect();
+    assert_eq!(mix_channels(&frames, 4, &[2]), vec![0.0, 1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn mix_channels_averages_subset() {
+    let frames = [0.2f32, 0.4, -0.8, 0.9, 0.6, 0.0, 1.0, -1.0];
+    let mono = mix_channels(&frames, 4, &[0, 1]);
+    assert!((mono[0] - 0.3).abs() < 1e-6);
+    assert!((mono[1] - 0.3).abs() < 1e-6);
+}
+
+#[test]
+fn mix_channels_defaults_to_average() {
+    let frames = [0.5f32, -0.5, 1.0, 0.0];
+    assert_eq!(mix_channels(&frames, 2, &[]), vec![0.0, 0.5]);
+    // Channels the device doesn't have fall back to averaging
+    assert_eq!(mix_channels(&frames, 2, &[5]), vec![0.0, 0.5]);
+}
+
+#[test]
+fn mono_tail_uses_channel_selection() {
+    let samples: Vec<f32> = (0..10).flat_map(|_| [0.0, 0.5]).collect();
+    let mut buffer = CaptureBuffer::new(2, usize::MAX, 0);
+    buffer.push(&samples);
+    let buffer = std::sync::Mutex::new(buffer);
+    let tail = mono_tail(&buffer, 2, &[1], 10, 1);
+    assert_eq!(tail, vec![0.5f32; 10]);
+}
+
+#[test]
+fn channel_selection_parses_one_based_lists() {
+    assert_eq!(parse_channel_selection("").unwrap(), Vec::<u16>::new());
+    assert_eq!(parse_channel_selection("all").unwrap(), Vec::<u16>::new());
+    assert_eq!(parse_channel_selection("3").unwrap(), vec![2]);
+    assert_eq!(parse_channel_selection(" 2, 1,2 ").unwrap(), vec![0, 1]);
+    assert!(parse_channel_selection("0").is_err());
+    assert!(parse_channel_selection("1,x").is_err());
+    assert_eq!(format_channel_selection(&[0, 2]), "1,3");
+    assert_eq!(format_channel_selection(&[]), "");
+}
+
+#[test]
+fn signed_samples_convert_to_unit_range() {
+    assert_eq!(0i8.to_f32(), 0.0);
+    assert_eq!(i8::MAX.to_f32(), 1.0);
+    assert_eq!(i8::MIN.to_f32(), -1.0);
+    assert_eq!(i16::MAX.to_f32(), 1.0);
+    assert_eq!(i16::MIN.to_f32(), -1.0);
+    assert_eq!(i32::MAX.to_f32(), 1.0);
+    assert_eq!(i32::MIN.to_f32(), -1.0);
+    assert_eq!(i64::MAX.to_f32(), 1.0);
+    assert_eq!(i64::MIN.to_f32(), -1.0);
+    assert!(((i32::MAX / 2).to_f32() - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn i16_conversion_matches_wav_scaling() {
+    // Same scale the WAV decoders use, so device and file audio agree
+    for s in [-12345i16, -1, 0, 1, 16384, 32000] {
+        assert_eq!(s.to_f32(), s as f32 / i16::MAX as f32);
+    }
+}
+
+#[test]
+fn unsigned_samples_are_centered_on_midpoint() {
+    assert_eq!(128u8.to_f32(), 0.0);
+    assert_eq!(u8::MAX.to_f32(), 1.0);
+    assert_eq!(0u8.to_f32(), -1.0);
+    assert_eq!(32768u16.to_f32(), 0.0);
+    assert_eq!(u16::MAX.to_f32(), 1.0);
+    assert_eq!(0u16.to_f32(), -1.0);
+    assert_eq!((1u32 << 31).to_f32(), 0.0);
+    assert_eq!(u32::MAX.to_f32(), 1.0);
+    assert_eq!((1u64 << 63).to_f32(), 0.0);
+    assert_eq!(0u64.to_f32(), -1.0);
+}
+
+#[test]
+fn unsigned_matches_signed_after_offset() {
+    assert_eq!(200u8.to_f32(), 72i8.to_f32());
+    assert_eq!(1000u16.to_f32(), (-31768i16).to_f32());
+    assert_eq!(3_000_000_000u32.to_f32(), 852_516_352i32.to_f32());
+}
+
+#[test]
+fn float_samples_pass_through() {
+    assert_eq!(0.25f32.to_f32(), 0.25);
+    assert_eq!((-0.5f64).to_f32(), -0.5);
+    assert_eq!(
+        samples_to_f32(&[0.0f64, 1.0, -1.0, 0.125]),
+        vec![0.0, 1.0, -1.0, 0.125]
+    );
+}
+
+#[test]
+fn samples_to_f32_converts_whole_buffer() {
+    let out = samples_to_f32(&[0i32, i32::MAX, i32::MIN]);
+    assert_eq!(out, vec![0.0, 1.0, -1.0]);
+    let out = samples_to_f32(&[128u8, 255, 0]);
+    assert_eq!(out, vec![0.0, 1.0, -1.0]);
+}
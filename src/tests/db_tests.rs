@@ -1,4 +1,5 @@
 use crate::db::Db;
+use crate::transcript::Segment;
 use std::path::PathBuf;
 
 fn temp_db() -> (Db, tempfile::TempDir) {
@@ -71,3 +72,312 @@ fn open_at_nonexistent_path_creates_file() {
     // This tests that we get a proper error rather than a panic
     assert!(result.is_err() || PathBuf::from(&path).exists());
 }
+
+#[test]
+fn settings_accepts_input_device() {
+    let (db, _dir) = temp_db();
+    db.set_setting("input_device", "USB Headset Mono").unwrap();
+    assert_eq!(
+        db.get_setting("input_device").unwrap(),
+        Some("USB Headset Mono".to_string())
+    );
+
+    // Empty string selects the system default again
+    db.set_setting("input_device", "").unwrap();
+    assert_eq!(db.get_setting("input_device").unwrap(), Some(String::new()));
+}
+
+#[test]
+fn settings_accepts_audio_host() {
+    let (db, _dir) = temp_db();
+    db.set_setting("audio_host", "JACK").unwrap();
+    assert_eq!(
+        db.get_setting("audio_host").unwrap(),
+        Some("JACK".to_string())
+    );
+}
+
+#[test]
+fn settings_accepts_per_device_channel_selection() {
+    let (db, _dir) = temp_db();
+    db.set_setting("input_channels:Scarlett 4i4", "1,3")
+        .unwrap();
+    db.set_setting("input_channels:", "2").unwrap();
+    assert_eq!(
+        db.get_setting("input_channels:Scarlett 4i4").unwrap(),
+        Some("1,3".to_string())
+    );
+    assert_eq!(
+        db.get_setting("input_channels:").unwrap(),
+        Some("2".to_string())
+    );
+}
+
+#[test]
+fn settings_accepts_recording_toggles() {
+    let (db, _dir) = temp_db();
+    for key in [
+        "auto_stop",
+        "live_preview",
+        "record_mode",
+        "retain_audio",
+        "pre_roll",
+        "translate",
+        "carry_context",
+    ] {
+        assert!(
+            db.set_setting(key, "true").is_ok(),
+            "{key} should be accepted"
+        );
+    }
+    db.set_setting("record_mode", "hold").unwrap();
+    assert_eq!(
+        db.get_setting("record_mode").unwrap(),
+        Some("hold".to_string())
+    );
+}
+
+#[test]
+fn settings_accepts_decoding_profile() {
+    let (db, _dir) = temp_db();
+    for preset in ["fast", "balanced", "accurate"] {
+        db.set_setting("decoding_profile", preset).unwrap();
+        assert_eq!(
+            db.get_setting("decoding_profile").unwrap(),
+            Some(preset.to_string())
+        );
+    }
+}
+
+#[test]
+fn settings_accepts_preprocess_toggles() {
+    let (db, _dir) = temp_db();
+    for key in [
+        "preprocess_denoise",
+        "preprocess_highpass",
+        "preprocess_normalize",
+    ] {
+        db.set_setting(key, "true").unwrap();
+        assert_eq!(db.get_setting(key).unwrap(), Some("true".to_string()));
+    }
+}
+
+#[test]
+fn recording_roundtrip() {
+    let (db, _dir) = temp_db();
+    let id = db.insert("with audio").unwrap();
+    let other = db.insert("without audio").unwrap();
+    db.save_recording(id, b"compressed").unwrap();
+
+    assert_eq!(db.recording(id).unwrap(), Some(b"compressed".to_vec()));
+    assert_eq!(db.recording(other).unwrap(), None);
+
+    let recent = db.recent(10).unwrap();
+    assert_eq!(recent[0].id, other);
+    assert!(!recent[0].has_audio);
+    assert_eq!(recent[1].id, id);
+    assert!(recent[1].has_audio);
+}
+
+#[test]
+fn update_text_replaces_entry() {
+    let (db, _dir) = temp_db();
+    let id = db.insert("first try").unwrap();
+    db.update_text(id, "second try").unwrap();
+    let recent = db.recent(10).unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].text, "second try");
+}
+
+#[test]
+fn language_is_stored_with_entry() {
+    let (db, _dir) = temp_db();
+    let pt = db.insert_with_language("olá mundo", Some("pt")).unwrap();
+    db.insert("no language").unwrap();
+    let recent = db.recent(10).unwrap();
+    assert_eq!(recent[0].language, None);
+    assert_eq!(recent[1].language.as_deref(), Some("pt"));
+
+    // Re-transcription may detect a different language
+    db.set_language(pt, Some("gl")).unwrap();
+    assert_eq!(db.recent(10).unwrap()[1].language.as_deref(), Some("gl"));
+}
+
+#[test]
+fn open_adds_language_column_to_old_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("old.db");
+    {
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE transcriptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                text TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+            );
+            INSERT INTO transcriptions (text) VALUES ('from before');",
+        )
+        .unwrap();
+    }
+    let db = Db::open(&path).unwrap();
+    let recent = db.recent(10).unwrap();
+    assert_eq!(recent[0].text, "from before");
+    assert_eq!(recent[0].language, None);
+    db.insert_with_language("after", Some("de")).unwrap();
+
+    // Reopening an already migrated database is a no-op
+    drop(db);
+    let db = Db::open(&path).unwrap();
+    assert_eq!(db.recent(10).unwrap().len(), 2);
+}
+
+#[test]
+fn segments_roundtrip() {
+    let (db, _dir) = temp_db();
+    let id = db.insert("hello world").unwrap();
+    let plain = db.insert("no timings").unwrap();
+    assert!(db.segments(id).unwrap().is_empty());
+
+    let segments = vec![
+        Segment {
+            start_ms: 0,
+            end_ms: 800,
+            text: "hello".to_string(),
+        },
+        Segment {
+            start_ms: 800,
+            end_ms: 1600,
+            text: "world".to_string(),
+        },
+    ];
+    db.set_segments(id, &segments).unwrap();
+    assert_eq!(db.segments(id).unwrap(), segments);
+
+    let recent = db.recent(10).unwrap();
+    assert_eq!(recent[0].id, plain);
+    assert!(!recent[0].has_segments);
+    assert!(recent[1].has_segments);
+}
+
+#[test]
+fn glossary_add_list_remove() {
+    let (db, _dir) = temp_db();
+    assert!(db.glossary().unwrap().is_empty());
+    assert!(db.add_glossary_term("WhisperCrabs").unwrap());
+    assert!(db.add_glossary_term("  Usier ").unwrap());
+    assert_eq!(db.glossary().unwrap(), vec!["WhisperCrabs", "Usier"]);
+
+    // Duplicates (in any case) and blanks are ignored
+    assert!(!db.add_glossary_term("whispercrabs").unwrap());
+    assert!(!db.add_glossary_term("   ").unwrap());
+    assert_eq!(db.glossary().unwrap().len(), 2);
+
+    assert!(db.remove_glossary_term("WHISPERCRABS").unwrap());
+    assert!(!db.remove_glossary_term("missing").unwrap());
+    assert_eq!(db.glossary().unwrap(), vec!["Usier"]);
+}
+
+#[test]
+fn glossary_caps_term_length() {
+    let (db, _dir) = temp_db();
+    db.add_glossary_term(&"x".repeat(500)).unwrap();
+    assert_eq!(db.glossary().unwrap()[0].len(), 100);
+}
+
+#[test]
+fn settings_accepts_language() {
+    let (db, _dir) = temp_db();
+    db.set_setting("language", "auto").unwrap();
+    db.set_setting("language", "pt").unwrap();
+    assert_eq!(db.get_setting("language").unwrap(), Some("pt".to_string()));
+}
+
+#[test]
+fn prune_recordings_keeps_newest_within_budget() {
+    let (db, _dir) = temp_db();
+    let ids: Vec<i64> = (0..4)
+        .map(|i| {
+            let id = db.insert(&format!("entry {i}")).unwrap();
+            db.save_recording(id, &[0u8; 100]).unwrap();
+            id
+        })
+        .collect();
+
+    let removed = db.prune_recordings(250, 0).unwrap();
+    assert_eq!(removed, 2);
+    assert!(db.recording(ids[0]).unwrap().is_none());
+    assert!(db.recording(ids[1]).unwrap().is_none());
+    assert!(db.recording(ids[2]).unwrap().is_some());
+    assert!(db.recording(ids[3]).unwrap().is_some());
+    // The transcripts themselves stay
+    assert_eq!(db.recent(10).unwrap().len(), 4);
+}
+
+#[test]
+fn prune_recordings_keeps_newest_even_over_budget() {
+    let (db, _dir) = temp_db();
+    let old = db.insert("old").unwrap();
+    db.save_recording(old, &[0u8; 100]).unwrap();
+    let new = db.insert("new").unwrap();
+    db.save_recording(new, &[0u8; 500]).unwrap();
+
+    assert_eq!(db.prune_recordings(250, 0).unwrap(), 1);
+    assert!(db.recording(old).unwrap().is_none());
+    assert!(db.recording(new).unwrap().is_some());
+}
+
+#[test]
+fn prune_recordings_age_keeps_fresh_and_zero_disables() {
+    let (db, _dir) = temp_db();
+    let id = db.insert("fresh").unwrap();
+    db.save_recording(id, &[0u8; 100]).unwrap();
+
+    assert_eq!(db.prune_recordings(0, 30).unwrap(), 0);
+    assert_eq!(db.prune_recordings(0, 0).unwrap(), 0);
+    assert!(db.recording(id).unwrap().is_some());
+}
+
+#[test]
+fn custom_models_register_and_forget() {
+    use crate::config::CustomModel;
+    let (db, _dir) = temp_db();
+    assert!(db.custom_models().unwrap().is_empty());
+
+    let a = CustomModel {
+        id: "local:a".into(),
+        label: "a".into(),
+        path: PathBuf::from("/models/a.bin"),
+    };
+    let b = CustomModel {
+        id: "local:b".into(),
+        label: "b".into(),
+        path: PathBuf::from("/models/b.bin"),
+    };
+    db.add_custom_model(&a).unwrap();
+    db.add_custom_model(&b).unwrap();
+
+    // Re-importing keeps the position but takes the new path
+    let moved = CustomModel {
+        path: PathBuf::from("/elsewhere/a.bin"),
+        ..a.clone()
+    };
+    db.add_custom_model(&moved).unwrap();
+    assert_eq!(db.custom_models().unwrap(), vec![moved, b.clone()]);
+
+    assert!(db.remove_custom_model("local:a").unwrap());
+    assert!(!db.remove_custom_model("local:a").unwrap());
+    assert_eq!(db.custom_models().unwrap(), vec![b]);
+}
+
+#[test]
+fn touch_model_records_last_use() {
+    let (db, _dir) = temp_db();
+    assert!(db.model_usage().unwrap().is_empty());
+    db.touch_model("local-tiny").unwrap();
+    db.touch_model("local-tiny").unwrap();
+    db.touch_model("local:mine").unwrap();
+    let usage = db.model_usage().unwrap();
+    assert_eq!(usage.len(), 2);
+    // "YYYY-MM-DD HH:MM:SS", which sorts by time
+    assert_eq!(usage["local-tiny"].len(), 19);
+}
@@ -0,0 +1,520 @@
+use crate::download::{
+    CANCELLED, Checksum, Resume, TransferRate, fetch, format_eta, parse_content_range, part_path,
+    progress_detail, resume_action, sha256_file, sha256_from_etag,
+};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+const MB: u64 = 1024 * 1024;
+
+/// SHA-256 of "abc" (FIPS 180-2 test vector)
+const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+/// Helper: serve `body` over HTTP on localhost like a HuggingFace LFS file,
+/// with `etag` as its `X-Linked-Etag`. `honor_range` off makes the server
+/// ignore Range headers; `cut_at` drops full (non-range) downloads after
+/// that many bytes. Returns the file's URL.
+fn serve(body: Vec<u8>, etag: Option<String>, honor_range: bool, cut_at: Option<usize>) -> String {
+    serve_with_head(body, etag, honor_range, cut_at, None)
+}
+
+/// Helper: [`serve`], answering HEAD requests with `head_status` when given.
+fn serve_with_head(
+    body: Vec<u8>,
+    etag: Option<String>,
+    honor_range: bool,
+    cut_at: Option<usize>,
+    head_status: Option<&'static str>,
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/model.bin", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = Vec::new();
+            let mut byte = [0u8; 1];
+            while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap_or(0) == 1 {
+                request.push(byte[0]);
+            }
+            let request = String::from_utf8_lossy(&request).to_lowercase();
+            let range_start = request
+                .lines()
+                .find_map(|l| l.strip_prefix("range: bytes="))
+                .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok())
+                .filter(|_| honor_range);
+            let etag_header = etag
+                .as_ref()
+                .map(|e| format!("X-Linked-Etag: \"{e}\"\r\n"))
+                .unwrap_or_default();
+            let (status, extra, content) = match range_start {
+                Some(start) if start >= body.len() => (
+                    "416 Range Not Satisfiable",
+                    format!("Content-Range: bytes */{}\r\n", body.len()),
+                    &body[..0],
+                ),
+                Some(start) => (
+                    "206 Partial Content",
+                    format!(
+                        "Content-Range: bytes {start}-{}/{}\r\n",
+                        body.len() - 1,
+                        body.len()
+                    ),
+                    &body[start..],
+                ),
+                None => ("200 OK", String::new(), &body[..]),
+            };
+            let head = format!(
+                "HTTP/1.1 {status}\r\n{etag_header}{extra}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                content.len()
+            );
+            if let Some(status) = head_status.filter(|_| request.starts_with("head")) {
+                let reply =
+                    format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                let _ = stream.write_all(reply.as_bytes());
+                continue;
+            }
+            let _ = stream.write_all(head.as_bytes());
+            if request.starts_with("get") {
+                let sent = match cut_at {
+                    Some(cut) if range_start.is_none() => &content[..cut.min(content.len())],
+                    _ => content,
+                };
+                let _ = stream.write_all(sent);
+            }
+        }
+    });
+    url
+}
+
+/// Helper: a body of `len` varying bytes, and its SHA-256
+fn body(len: usize) -> (Vec<u8>, String) {
+    let body: Vec<u8> = (0..len).map(|i| (i * 7 % 251) as u8).collect();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("body");
+    std::fs::write(&path, &body).unwrap();
+    let sha256 = sha256_file(&path).unwrap();
+    (body, sha256)
+}
+
+/// Helper: check a download against what its own server publishes
+fn published(url: &str) -> Checksum {
+    Checksum::Published(url.to_string())
+}
+
+#[test]
+fn sha256_file_matches_test_vector() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("abc");
+    std::fs::write(&path, b"abc").unwrap();
+    assert_eq!(sha256_file(&path).unwrap(), ABC_SHA256);
+}
+
+#[test]
+fn part_path_appends_to_full_file_name() {
+    assert_eq!(
+        part_path(std::path::Path::new("/m/ggml-tiny.en.bin")),
+        std::path::Path::new("/m/ggml-tiny.en.bin.part")
+    );
+    assert_eq!(
+        part_path(std::path::Path::new("/m/amy.onnx.json")),
+        std::path::Path::new("/m/amy.onnx.json.part")
+    );
+}
+
+#[test]
+fn sha256_from_etag_accepts_only_sha256() {
+    let upper = ABC_SHA256.to_uppercase();
+    assert_eq!(
+        sha256_from_etag(&format!("\"{upper}\"")).as_deref(),
+        Some(ABC_SHA256)
+    );
+    assert_eq!(
+        sha256_from_etag(&format!("W/\"{ABC_SHA256}\"")).as_deref(),
+        Some(ABC_SHA256)
+    );
+    // Git blob hashes of small files are SHA-1
+    assert_eq!(
+        sha256_from_etag("\"a9993e364706816aba3e25717850c26c9cd0d89d\""),
+        None
+    );
+    assert_eq!(sha256_from_etag(&format!("\"{}\"", "z".repeat(64))), None);
+}
+
+#[test]
+fn parse_content_range_reads_start_and_total() {
+    assert_eq!(
+        parse_content_range("bytes 100-199/200"),
+        Some((100, Some(200)))
+    );
+    assert_eq!(parse_content_range("bytes 0-9/*"), Some((0, None)));
+    assert_eq!(parse_content_range("bytes */200"), None);
+    assert_eq!(parse_content_range("items 0-9/10"), None);
+}
+
+#[test]
+fn resume_action_follows_status() {
+    assert_eq!(
+        resume_action(206, Some("bytes 100-199/200"), 100),
+        Ok(Resume::Append)
+    );
+    assert!(resume_action(206, Some("bytes 0-199/200"), 100).is_err());
+    assert!(resume_action(206, None, 100).is_err());
+    assert_eq!(resume_action(200, None, 100), Ok(Resume::Restart));
+    assert_eq!(
+        resume_action(416, Some("bytes */200"), 200),
+        Ok(Resume::Complete)
+    );
+    assert_eq!(
+        resume_action(416, Some("bytes */150"), 200),
+        Ok(Resume::Discard)
+    );
+    assert!(resume_action(416, None, 0).is_err());
+    assert!(resume_action(404, None, 0).is_err());
+}
+
+#[test]
+fn fetch_downloads_and_verifies() {
+    let (data, sha256) = body(200_000);
+    let url = serve(data.clone(), Some(sha256), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    let mut last = None;
+    fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(false),
+        |downloaded, total| last = Some((downloaded, total)),
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+    assert!(!part_path(&dest).exists());
+    assert_eq!(last, Some((200_000, Some(200_000))));
+}
+
+#[test]
+fn fetch_resumes_an_interrupted_download() {
+    let (data, sha256) = body(200_000);
+    let url = serve(data.clone(), Some(sha256), true, Some(70_000));
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+
+    assert!(
+        fetch(
+            &url,
+            &dest,
+            &published(&url),
+            &AtomicBool::new(false),
+            |_, _| {}
+        )
+        .is_err()
+    );
+    assert!(!dest.exists());
+    let part = part_path(&dest);
+    assert_eq!(std::fs::metadata(&part).unwrap().len(), 70_000);
+
+    let mut first = None;
+    fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(false),
+        |downloaded, total| {
+            first.get_or_insert((downloaded, total));
+        },
+    )
+    .unwrap();
+    assert_eq!(first, Some((70_000, Some(200_000))));
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+    assert!(!part.exists());
+}
+
+#[test]
+fn fetch_restarts_when_range_is_ignored() {
+    let (data, sha256) = body(50_000);
+    let url = serve(data.clone(), Some(sha256), false, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    std::fs::write(part_path(&dest), b"stale bytes").unwrap();
+    fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+}
+
+#[test]
+fn fetch_discards_a_part_longer_than_the_file() {
+    let (data, sha256) = body(50_000);
+    let url = serve(data.clone(), Some(sha256), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    std::fs::write(part_path(&dest), vec![1u8; 60_000]).unwrap();
+    fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+}
+
+#[test]
+fn fetch_finishes_a_complete_part() {
+    let (data, sha256) = body(50_000);
+    let url = serve(data.clone(), Some(sha256), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    std::fs::write(part_path(&dest), &data).unwrap();
+    fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+}
+
+#[test]
+fn fetch_rejects_a_checksum_mismatch() {
+    let (data, _) = body(50_000);
+    let url = serve(data, Some(ABC_SHA256.into()), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    let err = fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap_err();
+    assert!(err.contains("Checksum mismatch"), "{err}");
+    assert!(!dest.exists());
+    // A corrupt part would fail again on resume, so it is dropped
+    assert!(!part_path(&dest).exists());
+}
+
+#[test]
+fn fetch_accepts_files_without_a_published_checksum() {
+    let (data, _) = body(1000);
+    let url = serve(data.clone(), None, true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("voice.onnx.json");
+    fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+}
+
+#[test]
+fn fetch_checks_the_pinned_checksum_over_the_published_one() {
+    let (data, sha256) = body(50_000);
+    let url = serve(data.clone(), Some(ABC_SHA256.into()), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    fetch(
+        &url,
+        &dest,
+        &Checksum::Pinned(sha256),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+}
+
+#[test]
+fn fetch_rejects_a_file_not_matching_its_pinned_checksum() {
+    let (data, sha256) = body(50_000);
+    let url = serve(data, Some(sha256), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    let err = fetch(
+        &url,
+        &dest,
+        &Checksum::Pinned(ABC_SHA256.into()),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap_err();
+    assert!(err.contains("Checksum mismatch"), "{err}");
+    assert!(!dest.exists());
+    assert!(!part_path(&dest).exists());
+}
+
+#[test]
+fn fetch_looks_up_the_published_checksum_away_from_the_mirror() {
+    let (data, sha256) = body(50_000);
+    // The mirror vouches for its own (tampered) file...
+    let (tampered, tampered_sha256) = body(40_000);
+    let mirror = serve(tampered, Some(tampered_sha256), true, None);
+    // ...but huggingface.co publishes the real checksum
+    let canonical = serve(data.clone(), Some(sha256), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    let err = fetch(
+        &mirror,
+        &dest,
+        &published(&canonical),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap_err();
+    assert!(err.contains("Checksum mismatch"), "{err}");
+    assert!(!dest.exists());
+
+    let mirror = serve(data.clone(), None, true, None);
+    fetch(
+        &mirror,
+        &dest,
+        &published(&canonical),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+}
+
+#[test]
+fn checksum_prefers_the_pinned_one() {
+    assert_eq!(
+        Checksum::pinned_or_published(Some(ABC_SHA256), "http://hf/x".into()),
+        Checksum::Pinned(ABC_SHA256.into())
+    );
+    assert_eq!(
+        Checksum::pinned_or_published(None, "http://hf/x".into()),
+        Checksum::Published("http://hf/x".into())
+    );
+}
+
+#[test]
+fn fetch_downloads_when_head_is_not_allowed() {
+    let (data, _) = body(50_000);
+    let url = serve_with_head(
+        data.clone(),
+        None,
+        true,
+        None,
+        Some("405 Method Not Allowed"),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), data);
+}
+
+#[test]
+fn fetch_cancelled_deletes_the_part() {
+    let (data, sha256) = body(50_000);
+    let url = serve(data, Some(sha256), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("model.bin");
+    std::fs::write(part_path(&dest), vec![0u8; 1000]).unwrap();
+    let err = fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &AtomicBool::new(true),
+        |_, _| {},
+    )
+    .unwrap_err();
+    assert_eq!(err, CANCELLED);
+    assert!(!dest.exists());
+    assert!(!part_path(&dest).exists());
+}
+
+#[test]
+fn fetch_cancelled_mid_transfer_deletes_the_part() {
+    let (data, sha256) = body(1_000_000);
+    let url = serve(data, Some(sha256), true, None);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("voice.onnx");
+    let cancel = AtomicBool::new(false);
+    let mut cancelled_at = None;
+    let err = fetch(
+        &url,
+        &dest,
+        &published(&url),
+        &cancel,
+        |downloaded, total| {
+            if downloaded > 0 && cancelled_at.is_none() {
+                assert!(part_path(&dest).exists());
+                cancelled_at = Some(downloaded);
+                cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+            assert_eq!(total, Some(1_000_000));
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, CANCELLED);
+    assert!(cancelled_at.is_some_and(|at| at < 1_000_000));
+    assert!(!dest.exists());
+    assert!(!part_path(&dest).exists());
+}
+
+#[test]
+fn transfer_rate_averages_over_recent_window() {
+    let start = Instant::now();
+    let mut rate = TransferRate::default();
+    assert_eq!(rate.bytes_per_sec(), None);
+    rate.record(start, 0);
+    rate.record(start + Duration::from_millis(200), 100);
+    // Too short a span to judge
+    assert_eq!(rate.bytes_per_sec(), None);
+    rate.record(start + Duration::from_secs(2), 2 * MB);
+    assert_eq!(rate.bytes_per_sec(), Some(MB as f64));
+    // A stall long ago no longer drags the speed down
+    rate.record(start + Duration::from_secs(20), 2 * MB);
+    rate.record(start + Duration::from_secs(22), 6 * MB);
+    assert_eq!(rate.bytes_per_sec(), Some(2.0 * MB as f64));
+}
+
+#[test]
+fn format_eta_picks_units() {
+    assert_eq!(format_eta(0), "0 s");
+    assert_eq!(format_eta(45), "45 s");
+    assert_eq!(format_eta(200), "3 min 20 s");
+    assert_eq!(format_eta(180), "3 min");
+    assert_eq!(format_eta(1250), "20 min");
+    assert_eq!(format_eta(3900), "1 h 5 min");
+}
+
+#[test]
+fn progress_detail_shows_speed_and_time_left() {
+    assert_eq!(progress_detail(0, Some(100 * MB), None), "");
+    assert_eq!(
+        progress_detail(40 * MB, Some(100 * MB), Some(2.0 * MB as f64)),
+        "2.0 MB/s, 30 s left"
+    );
+    assert_eq!(
+        progress_detail(40 * MB, None, Some(2.0 * MB as f64)),
+        "2.0 MB/s"
+    );
+    assert_eq!(
+        progress_detail(100 * MB, Some(100 * MB), Some(2.0 * MB as f64)),
+        "2.0 MB/s"
+    );
+}
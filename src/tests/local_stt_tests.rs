@@ -0,0 +1,49 @@
+use crate::local_stt::{decimate, resample};
+
+/// Helper: sine wave of the given frequency
+fn sine(sample_rate: u32, freq: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| 0.5 * (std::f32::consts::TAU * freq * i as f32 / sample_rate as f32).sin())
+        .collect()
+}
+
+/// Helper: RMS of the middle half, away from filter edge effects
+fn mid_rms(samples: &[f32]) -> f32 {
+    let mid = &samples[samples.len() / 4..samples.len() * 3 / 4];
+    (mid.iter().map(|s| s * s).sum::<f32>() / mid.len() as f32).sqrt()
+}
+
+#[test]
+fn decimate_keeps_length_ratio() {
+    let out = decimate(&vec![0.1; 48000], 3);
+    assert_eq!(out.len(), 16000);
+    let out = decimate(&[0.1; 10], 3);
+    assert_eq!(out.len(), 4);
+}
+
+#[test]
+fn decimate_passes_speech_band() {
+    // 1 kHz at 48 kHz keeps its level at 16 kHz (0.5 peak = 0.354 RMS)
+    let out = decimate(&sine(48000, 1000.0, 48000), 3);
+    let rms = mid_rms(&out);
+    assert!((rms - 0.354).abs() < 0.01, "rms {rms}");
+}
+
+#[test]
+fn decimate_rejects_aliasing_tones() {
+    // 12 kHz is above the new 8 kHz Nyquist and would alias to 4 kHz
+    let out = decimate(&sine(48000, 12000.0, 48000), 3);
+    assert!(mid_rms(&out) < 0.005, "rms {}", mid_rms(&out));
+}
+
+#[test]
+fn decimate_by_one_is_identity() {
+    let input = sine(16000, 440.0, 100);
+    assert_eq!(decimate(&input, 1), input);
+}
+
+#[test]
+fn resample_uses_decimation_for_integer_ratios() {
+    let input = sine(48000, 440.0, 4800);
+    assert_eq!(resample(&input, 48000, 16000).unwrap(), decimate(&input, 3));
+}
@@ -1,6 +1,14 @@
 mod api_tests;
+mod audio_file_tests;
+mod audio_source_tests;
 mod audio_tests;
 mod config_load_tests;
 mod config_tests;
 mod db_tests;
+mod download_tests;
+mod local_stt_tests;
 mod model_download_tests;
+mod models_tests;
+mod preprocess_tests;
+mod retention_tests;
+mod transcript_tests;
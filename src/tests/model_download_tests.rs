@@ -9,7 +9,7 @@ fn model_urls_are_reachable() {
         .unwrap();
 
     for model in config::LOCAL_MODEL_PRESETS {
-        let url = config::model_url(model.file_name);
+        let url = model.url(config::DEFAULT_HF_ENDPOINT);
         let resp = client.head(&url).send();
         match resp {
             Ok(r) => {
@@ -37,7 +37,7 @@ fn model_download_tiny_to_tempdir() {
         .unwrap();
 
     let model = config::find_local_model("local-tiny").unwrap();
-    let url = config::model_url(model.file_name);
+    let url = config::model_url(config::DEFAULT_HF_ENDPOINT, model.file_name);
 
     let resp = match client.get(&url).send() {
         Ok(r) => r,
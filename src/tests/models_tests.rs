@@ -0,0 +1,101 @@
+use crate::config::{self, CustomModel};
+use crate::models::{InstalledModel, format_size, installed_models, lru_evictions};
+use std::collections::HashMap;
+
+/// Helper: a downloaded model of `mb` megabytes, last used at `last_used`
+fn model(id: &str, mb: u64, last_used: Option<&str>) -> InstalledModel {
+    InstalledModel {
+        id: id.to_string(),
+        label: id.to_string(),
+        path: format!("/models/{id}.bin").into(),
+        size: mb * 1024 * 1024,
+        last_used: last_used.map(str::to_string),
+        imported: false,
+    }
+}
+
+const MB: u64 = 1024 * 1024;
+
+#[test]
+fn installed_models_lists_files_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let tiny = config::find_local_model("local-tiny").unwrap();
+    std::fs::write(dir.path().join(tiny.file_name), vec![0u8; 1000]).unwrap();
+    let imported_path = dir.path().join("mine.bin");
+    std::fs::write(&imported_path, vec![0u8; 10]).unwrap();
+    let imported = [
+        CustomModel {
+            id: "local:mine".into(),
+            label: "mine".into(),
+            path: imported_path,
+        },
+        CustomModel {
+            id: "local:gone".into(),
+            label: "gone".into(),
+            path: dir.path().join("gone.bin"),
+        },
+    ];
+    let usage = HashMap::from([("local-tiny".to_string(), "2026-01-02 03:04:05".to_string())]);
+
+    let installed = installed_models(dir.path(), &imported, &usage);
+    assert_eq!(installed.len(), 2);
+    assert_eq!(installed[0].id, "local-tiny");
+    assert_eq!(installed[0].size, 1000);
+    assert_eq!(
+        installed[0].last_used.as_deref(),
+        Some("2026-01-02 03:04:05")
+    );
+    assert!(!installed[0].imported);
+    assert_eq!(installed[1].id, "local:mine");
+    assert!(installed[1].imported);
+    assert_eq!(installed[1].last_used, None);
+}
+
+#[test]
+fn lru_evictions_drop_oldest_until_within_budget() {
+    let models = [
+        model("a", 100, Some("2026-03-01 10:00:00")),
+        model("b", 100, Some("2026-01-01 10:00:00")),
+        model("c", 100, Some("2026-02-01 10:00:00")),
+    ];
+    let ids = |evicted: Vec<&InstalledModel>| -> Vec<String> {
+        evicted.into_iter().map(|m| m.id.clone()).collect()
+    };
+    assert_eq!(
+        ids(lru_evictions(&models, 300 * MB, &[])),
+        Vec::<String>::new()
+    );
+    assert_eq!(ids(lru_evictions(&models, 250 * MB, &[])), ["b"]);
+    assert_eq!(ids(lru_evictions(&models, 150 * MB, &[])), ["b", "c"]);
+    // The active model stays even when it is the oldest
+    assert_eq!(ids(lru_evictions(&models, 150 * MB, &["b"])), ["c", "a"]);
+}
+
+#[test]
+fn lru_evictions_prefer_never_used_models() {
+    let models = [
+        model("used", 100, Some("2026-01-01 10:00:00")),
+        model("unused", 100, None),
+    ];
+    let evicted = lru_evictions(&models, 150 * MB, &[]);
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(evicted[0].id, "unused");
+}
+
+#[test]
+fn lru_evictions_skip_imported_files_and_unlimited_budget() {
+    let mut imported = model("local:mine", 1000, None);
+    imported.imported = true;
+    let models = [imported, model("a", 100, Some("2026-01-01 10:00:00"))];
+    // Imported files neither count against the budget nor get deleted
+    assert!(lru_evictions(&models, 150 * MB, &[]).is_empty());
+    assert_eq!(lru_evictions(&models, 50 * MB, &[])[0].id, "a");
+    assert!(lru_evictions(&models, 0, &[]).is_empty());
+}
+
+#[test]
+fn format_size_uses_mb_then_gb() {
+    assert_eq!(format_size(75 * MB), "75 MB");
+    assert_eq!(format_size(1024 * MB), "1.0 GB");
+    assert_eq!(format_size(1600 * MB), "1.6 GB");
+}
@@ -0,0 +1,193 @@
+use crate::preprocess::{
+    MAX_GAIN, PEAK_CEILING, PreprocessOptions, TARGET_RMS, denoise, high_pass, normalize,
+    process_wav,
+};
+use std::io::Cursor;
+
+/// Helper: build a mono 16-bit WAV buffer from f32 samples
+fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let mut buf = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::new(&mut buf, spec).unwrap();
+    for &s in samples {
+        writer
+            .write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .unwrap();
+    }
+    writer.finalize().unwrap();
+    buf.into_inner()
+}
+
+/// Helper: decode a mono 16-bit WAV buffer back to f32 samples
+fn decode_wav(wav: &[u8]) -> (hound::WavSpec, Vec<f32>) {
+    let mut reader = hound::WavReader::new(Cursor::new(wav)).unwrap();
+    let spec = reader.spec();
+    let samples = reader
+        .samples::<i16>()
+        .map(|s| s.unwrap() as f32 / i16::MAX as f32)
+        .collect();
+    (spec, samples)
+}
+
+/// Helper: sine wave of the given frequency
+fn sine(sample_rate: u32, freq: f32, ms: u32, amplitude: f32) -> Vec<f32> {
+    let n = (sample_rate as u64 * ms as u64 / 1000) as usize;
+    (0..n)
+        .map(|i| amplitude * (std::f32::consts::TAU * freq * i as f32 / sample_rate as f32).sin())
+        .collect()
+}
+
+/// Helper: deterministic white noise in [-amplitude, amplitude]
+fn white_noise(len: usize, amplitude: f32) -> Vec<f32> {
+    let mut state: u32 = 0x1234_5678;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            amplitude * ((state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0)
+        })
+        .collect()
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+fn peak(samples: &[f32]) -> f32 {
+    samples.iter().fold(0.0f32, |m, s| m.max(s.abs()))
+}
+
+#[test]
+fn all_stages_off_is_passthrough() {
+    let samples = sine(16000, 440.0, 500, 0.3);
+    let wav = encode_wav(&samples, 16000);
+
+    let out = process_wav(&wav, PreprocessOptions::default()).unwrap();
+    let (spec, decoded) = decode_wav(&out);
+    assert_eq!(spec.sample_rate, 16000);
+    assert_eq!(spec.channels, 1);
+    let (_, original) = decode_wav(&wav);
+    assert_eq!(decoded, original);
+}
+
+#[test]
+fn options_any_reflects_toggles() {
+    assert!(!PreprocessOptions::default().any());
+    assert!(
+        PreprocessOptions {
+            normalize: true,
+            ..Default::default()
+        }
+        .any()
+    );
+}
+
+#[test]
+fn high_pass_attenuates_rumble() {
+    let mut rumble = sine(16000, 20.0, 1000, 0.5);
+    let before = rms(&rumble);
+    high_pass(&mut rumble, 16000, 80.0);
+    // 20 Hz is two octaves below the cutoff: ~-24 dB for a 2nd-order filter
+    assert!(rms(&rumble) < before * 0.1);
+}
+
+#[test]
+fn high_pass_keeps_speech_band() {
+    let mut voice = sine(16000, 1000.0, 1000, 0.5);
+    let before = rms(&voice);
+    high_pass(&mut voice, 16000, 80.0);
+    let after = rms(&voice);
+    assert!((after - before).abs() < before * 0.05);
+}
+
+#[test]
+fn normalize_boosts_quiet_input() {
+    let mut quiet = sine(16000, 440.0, 500, 0.01);
+    normalize(&mut quiet);
+    assert!((rms(&quiet) - TARGET_RMS).abs() < 0.01);
+    assert!(peak(&quiet) <= PEAK_CEILING + 1e-4);
+}
+
+#[test]
+fn normalize_respects_peak_ceiling() {
+    // One loud click in otherwise quiet audio: gain is limited by the click
+    let mut samples = sine(16000, 440.0, 500, 0.01);
+    samples[100] = 0.8;
+    normalize(&mut samples);
+    assert!(peak(&samples) <= PEAK_CEILING + 1e-4);
+}
+
+#[test]
+fn normalize_caps_gain() {
+    let mut faint = sine(16000, 440.0, 500, 0.001);
+    normalize(&mut faint);
+    let expected = 0.001 * std::f32::consts::FRAC_1_SQRT_2 * MAX_GAIN;
+    assert!((rms(&faint) - expected).abs() < expected * 0.05);
+}
+
+#[test]
+fn normalize_leaves_silence_alone() {
+    let mut silence = vec![0.0f32; 1600];
+    normalize(&mut silence);
+    assert!(silence.iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn denoise_reduces_white_noise() {
+    let noise = white_noise(48000, 0.1);
+    let clean = denoise(&noise, 48000).unwrap();
+    assert_eq!(clean.len(), noise.len());
+    assert!(rms(&clean) < rms(&noise) * 0.5);
+}
+
+#[test]
+fn denoise_resamples_other_rates() {
+    let noise = white_noise(16000, 0.1);
+    let clean = denoise(&noise, 16000).unwrap();
+    assert_eq!(clean.len(), noise.len());
+}
+
+#[test]
+fn process_wav_applies_enabled_stages() {
+    let mut samples = sine(16000, 20.0, 1000, 0.3);
+    for (s, v) in samples.iter_mut().zip(sine(16000, 1000.0, 1000, 0.02)) {
+        *s += v;
+    }
+    let wav = encode_wav(&samples, 16000);
+    let opts = PreprocessOptions {
+        high_pass: true,
+        normalize: true,
+        ..Default::default()
+    };
+
+    let (spec, out) = decode_wav(&process_wav(&wav, opts).unwrap());
+    assert_eq!(spec.sample_rate, 16000);
+    assert_eq!(out.len(), samples.len());
+    // Rumble removed, the remaining 1 kHz tone brought up to target level
+    assert!((rms(&out[1600..]) - TARGET_RMS).abs() < 0.02);
+}
+
+#[test]
+fn process_wav_rejects_stereo() {
+    let mut buf = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::new(&mut buf, spec).unwrap();
+    writer.write_sample(0i16).unwrap();
+    writer.write_sample(0i16).unwrap();
+    writer.finalize().unwrap();
+
+    let opts = PreprocessOptions {
+        normalize: true,
+        ..Default::default()
+    };
+    assert!(process_wav(&buf.into_inner(), opts).is_err());
+}
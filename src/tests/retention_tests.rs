@@ -0,0 +1,78 @@
+use crate::retention::{RETAINED_SAMPLE_RATE, compress_wav, decompress, decompress_to_wav};
+use std::io::Cursor;
+
+/// Helper: build a mono 16-bit WAV buffer from f32 samples
+fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let mut buf = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::new(&mut buf, spec).unwrap();
+    for &s in samples {
+        writer
+            .write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .unwrap();
+    }
+    writer.finalize().unwrap();
+    buf.into_inner()
+}
+
+/// Helper: sine wave of the given frequency
+fn sine(sample_rate: u32, freq: f32, len: usize, amplitude: f32) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (std::f32::consts::TAU * freq * i as f32 / sample_rate as f32).sin())
+        .collect()
+}
+
+#[test]
+fn compressed_is_about_a_quarter_of_pcm() {
+    let wav = encode_wav(&sine(16000, 440.0, 16000, 0.5), 16000);
+    let stored = compress_wav(&wav).unwrap();
+    assert_eq!(&stored[0..4], b"RIFF");
+    assert!(stored.len() < wav.len() / 3, "{} bytes", stored.len());
+}
+
+#[test]
+fn roundtrip_keeps_length_and_shape() {
+    // Not a multiple of the block size, so the last block is padded
+    let original = sine(16000, 440.0, 12345, 0.5);
+    let stored = compress_wav(&encode_wav(&original, 16000)).unwrap();
+    let decoded = decompress(&stored).unwrap();
+    assert_eq!(decoded.len(), original.len());
+
+    // ADPCM is lossy but a clean tone should come back close (> 20 dB SNR)
+    let (mut signal, mut noise) = (0.0f64, 0.0f64);
+    for (&o, &d) in original.iter().zip(&decoded) {
+        let o = (o * i16::MAX as f32) as f64;
+        signal += o * o;
+        noise += (o - d as f64).powi(2);
+    }
+    assert!(signal / noise > 100.0, "SNR too low: {}", signal / noise);
+}
+
+#[test]
+fn compress_resamples_to_16k() {
+    let wav = encode_wav(&sine(48000, 440.0, 48000, 0.5), 48000);
+    let decoded = decompress(&compress_wav(&wav).unwrap()).unwrap();
+    // One second at 16 kHz, give or take the resampler's block padding
+    assert!((decoded.len() as i64 - 16000).abs() < 1100);
+}
+
+#[test]
+fn decompress_to_wav_is_plain_pcm() {
+    let stored = compress_wav(&encode_wav(&sine(16000, 440.0, 4000, 0.3), 16000)).unwrap();
+    let wav = decompress_to_wav(&stored).unwrap();
+    let reader = hound::WavReader::new(Cursor::new(&wav)).unwrap();
+    assert_eq!(reader.spec().sample_rate, RETAINED_SAMPLE_RATE);
+    assert_eq!(reader.spec().channels, 1);
+    assert_eq!(reader.spec().bits_per_sample, 16);
+    assert_eq!(reader.len(), 4000);
+}
+
+#[test]
+fn decompress_rejects_garbage() {
+    assert!(decompress(b"not audio at all").is_err());
+}
@@ -0,0 +1,153 @@
+use crate::transcript::{
+    MAX_PROMPT_CHARS, MIN_CONTEXT_CHARS, Segment, SubtitleFormat, build_prompt, format_timestamp,
+    parse_export_request, subtitles,
+};
+use std::path::Path;
+
+fn segment(start_ms: u64, end_ms: u64, text: &str) -> Segment {
+    Segment {
+        start_ms,
+        end_ms,
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn timestamps_use_format_separator() {
+    assert_eq!(format_timestamp(0, SubtitleFormat::Srt), "00:00:00,000");
+    assert_eq!(
+        format_timestamp(3_723_045, SubtitleFormat::Srt),
+        "01:02:03,045"
+    );
+    assert_eq!(
+        format_timestamp(3_723_045, SubtitleFormat::WebVtt),
+        "01:02:03.045"
+    );
+}
+
+#[test]
+fn srt_numbers_cues_from_one() {
+    let segments = [segment(0, 1500, " Hello"), segment(1500, 4200, "world ")];
+    assert_eq!(
+        subtitles(&segments, SubtitleFormat::Srt),
+        "1\n00:00:00,000 --> 00:00:01,500\nHello\n\n\
+         2\n00:00:01,500 --> 00:00:04,200\nworld\n\n"
+    );
+}
+
+#[test]
+fn webvtt_has_header_and_no_numbers() {
+    let segments = [segment(250, 1000, "Olá")];
+    assert_eq!(
+        subtitles(&segments, SubtitleFormat::WebVtt),
+        "WEBVTT\n\n00:00:00.250 --> 00:00:01.000\nOlá\n\n"
+    );
+}
+
+#[test]
+fn subtitles_skip_empty_segments_and_zero_length_cues() {
+    let segments = [
+        segment(0, 500, "  "),
+        segment(500, 500, "Hi"),
+        segment(600, 900, "there"),
+    ];
+    let srt = subtitles(&segments, SubtitleFormat::Srt);
+    assert!(srt.starts_with("1\n00:00:00,500 --> 00:00:00,501\nHi\n\n2\n"));
+    assert_eq!(srt.matches(" --> ").count(), 2);
+}
+
+#[test]
+fn subtitle_format_follows_extension() {
+    assert_eq!(
+        SubtitleFormat::from_path(Path::new("talk.vtt")),
+        SubtitleFormat::WebVtt
+    );
+    assert_eq!(
+        SubtitleFormat::from_path(Path::new("talk.VTT")),
+        SubtitleFormat::WebVtt
+    );
+    assert_eq!(
+        SubtitleFormat::from_path(Path::new("talk.srt")),
+        SubtitleFormat::Srt
+    );
+    assert_eq!(
+        SubtitleFormat::from_path(Path::new("talk")),
+        SubtitleFormat::Srt
+    );
+}
+
+#[test]
+fn export_request_takes_id_and_path() {
+    assert_eq!(
+        parse_export_request("42:/home/me/talk.srt"),
+        Ok((42, "/home/me/talk.srt"))
+    );
+    // Only the first colon separates
+    assert_eq!(
+        parse_export_request("7:C:\\subs\\talk.vtt"),
+        Ok((7, "C:\\subs\\talk.vtt"))
+    );
+    assert!(parse_export_request("/home/me/talk.srt").is_err());
+    assert!(parse_export_request("latest:/tmp/a.srt").is_err());
+    assert!(parse_export_request("3:").is_err());
+}
+
+fn terms(list: &[&str]) -> Vec<String> {
+    list.iter().map(|t| t.to_string()).collect()
+}
+
+#[test]
+fn build_prompt_lists_glossary_then_previous_text() {
+    assert_eq!(build_prompt(&[], None), None);
+    assert_eq!(build_prompt(&terms(&[" ", ""]), Some("  ")), None);
+    assert_eq!(
+        build_prompt(&terms(&["WhisperCrabs", "Usier"]), None).as_deref(),
+        Some("WhisperCrabs, Usier.")
+    );
+    assert_eq!(
+        build_prompt(&[], Some(" Ship it on Friday. ")).as_deref(),
+        Some("Ship it on Friday.")
+    );
+    assert_eq!(
+        build_prompt(&terms(&["Groq"]), Some("Switch to the API.")).as_deref(),
+        Some("Groq. Switch to the API.")
+    );
+}
+
+#[test]
+fn build_prompt_keeps_end_of_long_previous_text() {
+    let previous = "word ".repeat(400) + "the end";
+    let prompt = build_prompt(&terms(&["Groq"]), Some(&previous)).unwrap();
+    assert!(prompt.chars().count() <= MAX_PROMPT_CHARS);
+    assert!(prompt.starts_with("Groq. word"));
+    assert!(prompt.ends_with("the end"));
+}
+
+#[test]
+fn build_prompt_sends_only_whole_glossary_terms() {
+    let glossary: Vec<String> = (0..100).map(|i| format!("Term{i:03}")).collect();
+    let prompt = build_prompt(&glossary, None).unwrap();
+    assert!(prompt.chars().count() <= MAX_PROMPT_CHARS);
+    assert!(prompt.ends_with('.'));
+    for term in prompt.trim_end_matches('.').split(", ") {
+        assert!(glossary.contains(&term.to_string()), "cut term {term:?}");
+    }
+}
+
+#[test]
+fn build_prompt_keeps_room_for_context_after_long_glossary() {
+    let glossary: Vec<String> = (0..100).map(|i| format!("Term{i:03}")).collect();
+    let previous = "word ".repeat(400) + "the end";
+    let prompt = build_prompt(&glossary, Some(&previous)).unwrap();
+    assert!(prompt.chars().count() <= MAX_PROMPT_CHARS);
+    let (terms, context) = prompt.split_once(". ").unwrap();
+    assert!(terms.starts_with("Term000, Term001"));
+    assert!(context.chars().count() >= MIN_CONTEXT_CHARS - 5);
+    assert!(context.ends_with("the end"));
+}
+
+#[test]
+fn build_prompt_strips_nul_bytes() {
+    let prompt = build_prompt(&terms(&["a\0b"]), None).unwrap();
+    assert_eq!(prompt, "ab.");
+}
@@ -0,0 +1,191 @@
+//! Transcription requests and results shared by the local and API backends,
+//! and their export as SRT / WebVTT subtitles.
+
+use crate::config::DecodingProfile;
+use serde::{Deserialize, Serialize};
+
+/// How a recording should be transcribed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TranscribeOptions {
+    /// Whisper language code of the speech, or `None` to detect it.
+    pub language: Option<String>,
+    /// Translate the speech to English instead of transcribing it as spoken.
+    pub translate: bool,
+    /// Text the speech is expected to follow on from: glossary terms and the
+    /// previous dictation. Biases spelling toward the words it contains.
+    pub prompt: Option<String>,
+    /// Beam size, threads and fallback for a local model (the API ignores it).
+    pub decoding: DecodingProfile,
+}
+
+/// Longest prompt passed to a backend. Whisper keeps at most 224 prompt
+/// tokens, and this stays under that for typical text.
+pub const MAX_PROMPT_CHARS: usize = 600;
+
+/// Part of the prompt kept for the previous transcript when there is one,
+/// however long the glossary is.
+pub const MIN_CONTEXT_CHARS: usize = 200;
+
+/// Prompt for a transcription: the glossary as a list of terms, then the end
+/// of the previous transcript, which whisper weighs most as it sits last.
+/// Only whole terms are sent, as many as fit in front of the context.
+/// `None` when there is neither.
+pub fn build_prompt(glossary: &[String], previous: Option<&str>) -> Option<String> {
+    // Whisper's C API takes a NUL-terminated string
+    let clean = |text: &str| -> String { text.chars().filter(|&c| c != '\0').collect() };
+    let previous = previous.map(clean).filter(|p| !p.trim().is_empty());
+    let term_budget = if previous.is_some() {
+        MAX_PROMPT_CHARS - MIN_CONTEXT_CHARS
+    } else {
+        MAX_PROMPT_CHARS
+    };
+
+    let mut prompt = String::new();
+    let mut len = 0;
+    for term in glossary.iter().map(|t| clean(t)) {
+        let term = term.trim();
+        if term.is_empty() {
+            continue;
+        }
+        // ", " before all but the first, "." after the last
+        let added = term.chars().count() + if len == 0 { 1 } else { 2 };
+        if len + added > term_budget {
+            break;
+        }
+        if len > 0 {
+            prompt.push_str(", ");
+        }
+        prompt.push_str(term);
+        len += added;
+    }
+    if !prompt.is_empty() {
+        prompt.push('.');
+    }
+
+    if let Some(previous) = previous {
+        let room = MAX_PROMPT_CHARS.saturating_sub(len + 1);
+        let tail = tail_words(previous.trim(), room);
+        if !tail.is_empty() {
+            if !prompt.is_empty() {
+                prompt.push(' ');
+            }
+            prompt.push_str(tail);
+        }
+    }
+    (!prompt.is_empty()).then_some(prompt)
+}
+
+/// The last whole words of `text` that fit in `max_chars`.
+fn tail_words(text: &str, max_chars: usize) -> &str {
+    let len = text.chars().count();
+    if len <= max_chars {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(len - max_chars)
+        .map_or(text.len(), |(i, _)| i);
+    let tail = &text[start..];
+    if text[..start].ends_with(char::is_whitespace) {
+        return tail.trim_start();
+    }
+    // Drop the word cut in half, unless it is the only one
+    match tail.find(char::is_whitespace) {
+        Some(space) => tail[space..].trim_start(),
+        None => tail,
+    }
+}
+
+/// A stretch of speech with its position in the recording.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// A finished transcription.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transcript {
+    pub text: String,
+    /// Language of the speech (whisper code, e.g. "pt"), when known.
+    pub language: Option<String>,
+    /// Timed segments, in order. Empty when the backend gave no timings.
+    pub segments: Vec<Segment>,
+}
+
+/// Subtitle file formats.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubtitleFormat {
+    Srt,
+    WebVtt,
+}
+
+impl SubtitleFormat {
+    /// Format for a file name: `.vtt` is WebVTT, anything else SubRip.
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("vtt") => Self::WebVtt,
+            _ => Self::Srt,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::WebVtt => "vtt",
+        }
+    }
+}
+
+/// Split an `export-subtitles` request, `<history id>:<path>`.
+pub fn parse_export_request(param: &str) -> Result<(i64, &str), String> {
+    let (id, path) = param
+        .split_once(':')
+        .ok_or("Expected <history id>:<path>")?;
+    let id = id
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid history id: {id:?}"))?;
+    if path.is_empty() {
+        return Err("Missing subtitle path".into());
+    }
+    Ok((id, path))
+}
+
+/// `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (WebVTT).
+pub fn format_timestamp(ms: u64, format: SubtitleFormat) -> String {
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::WebVtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Render segments as a subtitle file. Segments without text are skipped.
+pub fn subtitles(segments: &[Segment], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+    if format == SubtitleFormat::WebVtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    let cues = segments.iter().filter(|s| !s.text.trim().is_empty());
+    for (index, segment) in cues.enumerate() {
+        if format == SubtitleFormat::Srt {
+            out.push_str(&format!("{}\n", index + 1));
+        }
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(segment.start_ms, format),
+            // Zero-length cues are dropped by some players
+            format_timestamp(segment.end_ms.max(segment.start_ms + 1), format),
+            segment.text.trim()
+        ));
+    }
+    out
+}
@@ -9,6 +9,8 @@ use crate::audio::Recorder;
 use crate::config::{self, Config, TranscriptionService, TtsProvider};
 use crate::db::Db;
 use crate::local_stt::LocalWhisper;
+use crate::preprocess::PreprocessOptions;
+use crate::transcript::{SubtitleFormat, TranscribeOptions, Transcript, build_prompt, subtitles};
 use crate::tts::PiperTts;
 
 const MIC_SVG: &[u8] = include_bytes!("icons/microphone.svg");
@@ -65,6 +67,13 @@ const CSS: &str = r#"
         background-color: #991b1b;
         box-shadow: none;
     }
+    .mic-btn:drop(active) {
+        box-shadow: 0 0 0 3px rgba(255, 255, 255, 0.7);
+    }
+    .mic-btn.armed,
+    .mic-btn.armed:hover {
+        box-shadow: 0 0 0 3px #f59e0b;
+    }
     .mic-btn.recording,
     .mic-btn.recording:hover {
         background-image: none;
@@ -72,6 +81,12 @@ const CSS: &str = r#"
         box-shadow: none;
         animation: pulse 1s ease-in-out infinite;
     }
+    .mic-btn.paused,
+    .mic-btn.paused:hover {
+        background-image: none;
+        background-color: #2563eb;
+        box-shadow: none;
+    }
     .mic-btn.processing,
     .mic-btn.processing:hover {
         background-image: none;
@@ -109,6 +124,18 @@ const CSS: &str = r#"
         letter-spacing: 1px;
         margin-top: 4px;
     }
+    .level-meter trough {
+        min-height: 4px;
+        border-radius: 2px;
+        background-color: rgba(15, 23, 42, 0.55);
+    }
+    .level-meter block.filled {
+        border-radius: 2px;
+        background-color: #4ade80;
+    }
+    .level-meter.clipping block.filled {
+        background-color: #f87171;
+    }
     .status-label {
         color: #e2e8f0;
         font-size: 12px;
@@ -157,15 +184,465 @@ fn hide_status(label: &gtk4::Label) {
     label.set_opacity(0.0);
 }
 
+/// Settings key holding the channel selection of an input device ("" = system default).
+fn channel_setting_key(device: &str) -> String {
+    format!("input_channels:{device}")
+}
+
+/// Saved channel selection for an input device; empty averages all channels.
+fn load_channel_selection(db: &Arc<Mutex<Db>>, device: &str) -> Vec<u16> {
+    db.lock()
+        .ok()
+        .and_then(|d| d.get_setting(&channel_setting_key(device)).ok().flatten())
+        .and_then(|v| crate::audio::parse_channel_selection(&v).ok())
+        .unwrap_or_default()
+}
+
+/// Fill the Input Channels submenu for a device with `channels` inputs.
+fn fill_channel_menu(menu: &gtk4::gio::Menu, channels: u16) {
+    menu.remove_all();
+    let all = gtk4::gio::MenuItem::new(Some("All (Average)"), None);
+    all.set_action_and_target_value(Some("app.set-input-channels"), Some(&"".to_variant()));
+    menu.append_item(&all);
+    if channels > 1 {
+        for channel in 1..=channels {
+            let item = gtk4::gio::MenuItem::new(Some(&format!("Channel {channel}")), None);
+            item.set_action_and_target_value(
+                Some("app.set-input-channels"),
+                Some(&channel.to_string().to_variant()),
+            );
+            menu.append_item(&item);
+        }
+    }
+}
+
+/// Fill the Microphone submenu; "" selects the system default device.
+fn fill_mic_menu(menu: &gtk4::gio::Menu, devices: &[String]) {
+    menu.remove_all();
+    let default_item = gtk4::gio::MenuItem::new(Some("System Default"), None);
+    default_item.set_action_and_target_value(Some("app.set-input-device"), Some(&"".to_variant()));
+    menu.append_item(&default_item);
+    for name in devices {
+        // Device names may contain "::" or parentheses, so set the target
+        // value directly instead of using a detailed action string
+        let item = gtk4::gio::MenuItem::new(Some(name), None);
+        item.set_action_and_target_value(Some("app.set-input-device"), Some(&name.to_variant()));
+        menu.append_item(&item);
+    }
+}
+
+/// Privacy indicator: an amber ring while the mic is open for pre-roll.
+fn show_armed(btn: &gtk4::Button, armed: bool) {
+    if armed {
+        btn.add_css_class("armed");
+        btn.set_tooltip_text(Some("Microphone armed (pre-roll)"));
+    } else {
+        btn.remove_css_class("armed");
+        btn.set_tooltip_text(None);
+    }
+}
+
+/// Run the enabled cleanup stages; on failure, transcribe the raw recording.
+fn preprocess_wav(wav: Vec<u8>, opts: PreprocessOptions) -> Vec<u8> {
+    if !opts.any() {
+        return wav;
+    }
+    match crate::preprocess::process_wav(&wav, opts) {
+        Ok(processed) => processed,
+        Err(e) => {
+            eprintln!("Preprocessing failed, using raw audio: {e}");
+            wav
+        }
+    }
+}
+
+/// Compress a recording for retention; failures only cost the replay feature.
+fn compress_recording(wav: &mut std::fs::File) -> Option<Vec<u8>> {
+    crate::audio::read_wav(wav)
+        .and_then(|wav| crate::retention::compress_wav(&wav))
+        .map_err(|e| eprintln!("Recording not retained: {e}"))
+        .ok()
+}
+
+/// Show in-progress transcript text, keeping only the tail so the label stays small.
+/// Never raises the macOS error dialog, whatever the words happen to be.
+fn show_partial(label: &gtk4::Label, text: &str) {
+    const MAX_CHARS: usize = 32;
+    let count = text.chars().count();
+    let tail = if count > MAX_CHARS {
+        let skipped: String = text.chars().skip(count - MAX_CHARS).collect();
+        format!("…{}", skipped.trim_start())
+    } else {
+        text.to_string()
+    };
+    label.set_label(&tail);
+    label.set_opacity(1.0);
+}
+
+/// Seconds before the maximum recording length at which the countdown appears.
+const MAX_LENGTH_WARNING_SECS: u32 = 10;
+
+/// How long the input may sit at the noise floor before warning the user.
+const SILENT_MIC_WARNING: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// How often the live preview re-transcribes the growing recording.
+const PREVIEW_INTERVAL: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// Longest trailing window fed to a live preview pass (whisper's native window).
+const PREVIEW_WINDOW_SECS: u32 = 30;
+
+/// Re-transcribe the live sample buffer on a background thread until `stop` is set.
+/// Each partial transcript is sent over `tx`.
+#[allow(clippy::too_many_arguments)]
+fn spawn_live_preview(
+    whisper: Arc<LocalWhisper>,
+    buffer: Arc<Mutex<crate::audio::CaptureBuffer>>,
+    channels: u16,
+    selection: Vec<u16>,
+    sample_rate: u32,
+    options: TranscribeOptions,
+    stop: Arc<std::sync::atomic::AtomicBool>,
+    tx: std::sync::mpsc::Sender<String>,
+) -> std::thread::JoinHandle<()> {
+    use std::sync::atomic::Ordering;
+    let options = TranscribeOptions {
+        decoding: options.decoding.for_preview(),
+        ..options
+    };
+    std::thread::spawn(move || {
+        let mut last_len = 0;
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(PREVIEW_INTERVAL);
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            // Skip the pass if nothing new arrived since the last one
+            let total = buffer.lock().map(|b| b.len()).unwrap_or(0);
+            if total == last_len {
+                continue;
+            }
+            last_len = total;
+            let mono = crate::audio::mono_tail(
+                &buffer,
+                channels,
+                &selection,
+                sample_rate,
+                PREVIEW_WINDOW_SECS,
+            );
+            if mono.len() < sample_rate as usize {
+                continue;
+            }
+            match whisper.transcribe_samples(&mono, sample_rate, true, &options) {
+                Ok(partial) if !partial.text.is_empty() => {
+                    if tx.send(partial.text).is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => dbg_log!("[PREVIEW] partial pass failed: {e}"),
+            }
+        }
+        dbg_log!("[PREVIEW] worker stopped");
+    })
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum State {
     Idle,
     Recording,
+    /// Recording suspended; the captured audio is kept for when it resumes
+    Paused,
     Processing,
     Synthesizing,
     Speaking,
 }
 
+/// Whether a watcher started for recording `generation` should quit: that
+/// recording was stopped, or another one started within the same tick.
+fn recording_over(
+    state: &Rc<RefCell<State>>,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    generation: u64,
+) -> bool {
+    runtime.borrow().recording_generation != generation
+        || !matches!(*state.borrow(), State::Recording | State::Paused)
+}
+
+/// How long a dictation stays context for the next one when "Carry Context
+/// Forward" is on.
+const CONTEXT_CARRY_WINDOW: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Options for the next transcription: language settings plus a prompt built
+/// from the glossary and, with `follow_on`, the previous recent dictation.
+fn transcribe_options(
+    rt: &RuntimeState,
+    db: &Arc<Mutex<Db>>,
+    follow_on: bool,
+) -> TranscribeOptions {
+    let glossary = db
+        .lock()
+        .ok()
+        .and_then(|d| d.glossary().ok())
+        .unwrap_or_default();
+    let previous = rt
+        .last_transcript
+        .as_ref()
+        .filter(|(_, at)| follow_on && rt.carry_context && at.elapsed() < CONTEXT_CARRY_WINDOW)
+        .map(|(text, _)| text.as_str());
+    TranscribeOptions {
+        language: rt.language.clone(),
+        translate: rt.translate,
+        prompt: build_prompt(&glossary, previous),
+        decoding: rt.decoding.clone(),
+    }
+}
+
+/// Send a WAV to the active backend, then save the text to history and copy it.
+/// With `history_id`, the text of that existing entry is replaced instead.
+/// The caller has already moved the state machine to `Processing`.
+///
+/// The WAV stays in its temp file until the worker thread needs it, and is
+/// read again for retention instead of being copied up front. With
+/// `subtitles_out`, the segments are also written there as SRT or WebVTT.
+#[allow(clippy::too_many_arguments)]
+fn transcribe_and_copy(
+    mut wav: std::fs::File,
+    sample_rate: u32,
+    history_id: Option<i64>,
+    subtitles_out: Option<std::path::PathBuf>,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    state: &Rc<RefCell<State>>,
+    btn: &gtk4::Button,
+    status: &gtk4::Label,
+    config: &Arc<Config>,
+) {
+    let db_inner = Arc::clone(db);
+    let notify = config.sound_notification;
+
+    // Stop the live preview now rather than at its next poll; the final pass
+    // waits for its current pass so the two don't share the CPU
+    let preview = runtime.borrow_mut().live_preview_worker.take();
+    if let Some((stop, _)) = &preview {
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    let max_bytes = config.retain_audio_max_mb as u64 * 1024 * 1024;
+    let max_days = config.retain_audio_max_days;
+
+    // The transcript, plus the compressed recording when it is being retained
+    let (tx, rx) = std::sync::mpsc::channel::<Result<(Transcript, Option<Vec<u8>>), String>>();
+
+    let rt = runtime.borrow();
+    let preprocess = rt.preprocess;
+    let retain = rt.retain_audio && history_id.is_none();
+    // A re-transcription doesn't follow on from the latest dictation
+    let options = transcribe_options(&rt, db, history_id.is_none());
+    // Report the language back when the local model picked it
+    let show_language = rt.language.is_none()
+        && rt.active_service == TranscriptionService::Local
+        && rt
+            .local_whisper
+            .as_ref()
+            .is_some_and(|whisper| whisper.is_multilingual());
+    match rt.active_service {
+        TranscriptionService::Api => {
+            let base_url = rt.api_base_url.clone();
+            let api_key = rt.api_key.clone().unwrap_or_default();
+            let model = rt.api_model.clone();
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
+                let result = crate::audio::read_wav(&mut wav).and_then(|bytes| {
+                    let bytes = preprocess_wav(bytes, preprocess);
+                    rt.block_on(crate::api::transcribe(
+                        &base_url, &api_key, &model, bytes, &options,
+                    ))
+                });
+                let _ = tx.send(result.map(|text| {
+                    let audio = retain.then(|| compress_recording(&mut wav)).flatten();
+                    (text, audio)
+                }));
+            });
+        }
+        TranscriptionService::Local => {
+            if let Some(whisper) = rt.local_whisper.clone() {
+                if let Ok(d) = db.lock() {
+                    let _ = d.touch_model(&rt.active_provider);
+                }
+                std::thread::spawn(move || {
+                    if let Some((_, worker)) = preview {
+                        let _ = worker.join();
+                    }
+                    let result = crate::audio::read_wav(&mut wav).and_then(|bytes| {
+                        let bytes = preprocess_wav(bytes, preprocess);
+                        whisper.transcribe(&bytes, sample_rate, &options)
+                    });
+                    let _ = tx.send(result.map(|text| {
+                        let audio = retain.then(|| compress_recording(&mut wav)).flatten();
+                        (text, audio)
+                    }));
+                });
+            } else {
+                let _ = tx.send(Err("Local model not loaded".into()));
+            }
+        }
+    }
+    drop(rt);
+
+    let btn2 = btn.clone();
+    let st2 = status.clone();
+    let state_c2 = Rc::clone(state);
+    let runtime_c2 = Rc::clone(runtime);
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        match rx.try_recv() {
+            Ok(Ok((transcript, audio))) => {
+                let text = transcript.text;
+                let language = transcript.language;
+                let segments = transcript.segments;
+                if history_id.is_none() && !text.is_empty() {
+                    runtime_c2.borrow_mut().last_transcript =
+                        Some((text.clone(), std::time::Instant::now()));
+                }
+                if let Ok(db) = db_inner.lock() {
+                    match history_id {
+                        Some(id) => {
+                            if let Err(e) = db
+                                .update_text(id, &text)
+                                .and_then(|_| db.set_language(id, language.as_deref()))
+                                .and_then(|_| db.set_segments(id, &segments))
+                            {
+                                eprintln!("DB update error: {e}");
+                            }
+                        }
+                        None => match db.insert_with_language(&text, language.as_deref()) {
+                            Ok(id) => {
+                                if let Err(e) = db.set_segments(id, &segments) {
+                                    eprintln!("DB segments error: {e}");
+                                }
+                                if let Some(audio) = audio {
+                                    if let Err(e) = db.save_recording(id, &audio) {
+                                        eprintln!("DB recording error: {e}");
+                                    }
+                                    let _ = db.prune_recordings(max_bytes, max_days);
+                                }
+                            }
+                            Err(e) => eprintln!("DB insert error: {e}"),
+                        },
+                    }
+                }
+                let subtitles_saved = subtitles_out.as_ref().map(|path| {
+                    write_subtitles(&segments, path)
+                        .map_err(|e| eprintln!("Subtitle export error: {e}"))
+                        .is_ok()
+                });
+                match crate::input::copy_to_clipboard(&text) {
+                    Ok(_) => {
+                        if notify {
+                            play_notification();
+                        }
+                        btn2.remove_css_class("processing");
+                        btn2.add_css_class("done");
+
+                        let mut label = match language.filter(|_| show_language) {
+                            Some(code) => format!("Copied! ({})", code.to_uppercase()),
+                            None => "Copied!".to_string(),
+                        };
+                        match subtitles_saved {
+                            Some(true) => label.push_str(" Subtitles saved"),
+                            Some(false) => label.push_str(" Subtitles failed"),
+                            None => {}
+                        }
+                        show_status(&st2, &label);
+                        let st3 = st2.clone();
+                        let btn3 = btn2.clone();
+                        glib::timeout_add_local_once(
+                            std::time::Duration::from_secs(2),
+                            move || {
+                                hide_status(&st3);
+                                btn3.remove_css_class("done");
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!("Clipboard error: {e}");
+                        btn2.remove_css_class("processing");
+
+                        show_status(&st2, "Error!");
+                        let st3 = st2.clone();
+                        glib::timeout_add_local_once(
+                            std::time::Duration::from_secs(3),
+                            move || hide_status(&st3),
+                        );
+                    }
+                }
+                *state_c2.borrow_mut() = State::Idle;
+                glib::ControlFlow::Break
+            }
+            Ok(Err(e)) => {
+                eprintln!("Transcription error: {e}");
+                btn2.remove_css_class("processing");
+                show_status(&st2, "Error!");
+                let st3 = st2.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st3)
+                });
+                *state_c2.borrow_mut() = State::Idle;
+                glib::ControlFlow::Break
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(_) => {
+                *state_c2.borrow_mut() = State::Idle;
+                btn2.remove_css_class("processing");
+                glib::ControlFlow::Break
+            }
+        }
+    });
+}
+
+/// Propagation phase of the push-to-talk gesture: capture in hold mode so it
+/// sees presses before the button, none in toggle mode so it stays out of
+/// the way of clicks and window dragging.
+fn hold_phase(hold_to_talk: bool) -> gtk4::PropagationPhase {
+    if hold_to_talk {
+        gtk4::PropagationPhase::Capture
+    } else {
+        gtk4::PropagationPhase::None
+    }
+}
+
+/// Whether the active backend is a local model that only knows English, so
+/// it can transcribe but not translate.
+fn english_only_local(rt: &RuntimeState) -> bool {
+    rt.active_service == TranscriptionService::Local
+        && rt
+            .local_whisper
+            .as_ref()
+            .is_some_and(|whisper| !whisper.is_multilingual())
+}
+
+/// Why transcription can't start right now, if anything is missing.
+fn stt_unavailable(rt: &RuntimeState) -> Option<&'static str> {
+    // Guard: block recording during model download
+    if rt.downloading {
+        return Some("Downloading model...");
+    }
+
+    // Guard: Local mode without loaded model
+    if rt.active_service == TranscriptionService::Local && rt.local_whisper.is_none() {
+        return Some("No local model loaded");
+    }
+
+    // Guard: API mode — check if provider needs key and none is set
+    if rt.active_service == TranscriptionService::Api {
+        let needs_key = config::find_preset(&rt.active_provider)
+            .map(|p| p.needs_key)
+            .unwrap_or(true); // custom defaults to needing a key check
+        if needs_key && rt.api_key.is_none() {
+            return Some("No API key set");
+        }
+    }
+    None
+}
+
 struct RuntimeState {
     active_service: TranscriptionService,
     active_provider: String, // "groq", "ollama", ..., "custom", "local"
@@ -179,6 +656,30 @@ struct RuntimeState {
     tts_engine: Option<Arc<PiperTts>>,
     tts_downloading: bool,
     tts_stop: Arc<std::sync::atomic::AtomicBool>,
+    live_preview: bool,
+    hold_to_talk: bool,
+    preprocess: PreprocessOptions,
+    retain_audio: bool,
+    /// Spoken language (whisper code); `None` detects it per recording.
+    language: Option<String>,
+    /// Translate speech to English instead of transcribing it as spoken.
+    translate: bool,
+    /// Feed the previous dictation to the next one as context.
+    carry_context: bool,
+    /// Latest dictation and when it finished, for "Carry Context Forward".
+    last_transcript: Option<(String, std::time::Instant)>,
+    /// How local models decode: the chosen preset plus `.env` overrides.
+    decoding: config::DecodingProfile,
+    /// Stop flag and thread of the running live preview, if any.
+    live_preview_worker: Option<(
+        Arc<std::sync::atomic::AtomicBool>,
+        std::thread::JoinHandle<()>,
+    )>,
+    /// Cancel flags of the downloads in progress (model and voice).
+    active_downloads: Vec<Arc<std::sync::atomic::AtomicBool>>,
+    /// Bumped each time a recording starts, so its watchers can tell it
+    /// apart from one started right after it stopped.
+    recording_generation: u64,
 }
 
 pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
@@ -237,7 +738,15 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     status.add_css_class("status-label");
     status.set_opacity(0.0);
 
+    // Live input level while recording (transparent when idle so the layout doesn't shift)
+    let meter = gtk4::LevelBar::for_interval(0.0, 1.0);
+    meter.add_css_class("level-meter");
+    meter.set_size_request(56, 4);
+    meter.set_halign(gtk4::Align::Center);
+    meter.set_opacity(0.0);
+
     vbox.append(&button);
+    vbox.append(&meter);
 
     // On macOS there's no transparent window, so show branding
     #[cfg(target_os = "macos")]
@@ -281,6 +790,39 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         Db::open(&config.db_path).expect("Failed to open database"),
     ));
 
+    // WHISPER_MODEL picks the default local model, importing it if it is a file
+    let default_local_model = match &config.whisper_model {
+        Some(config::LocalModelSpec::Preset(id)) => id.to_string(),
+        Some(config::LocalModelSpec::File(path)) => {
+            match config::CustomModel::from_path(path).and_then(|model| {
+                let db = db.lock().map_err(|e| e.to_string())?;
+                let model = model.unique_among(&db.custom_models().map_err(|e| e.to_string())?);
+                db.add_custom_model(&model).map_err(|e| e.to_string())?;
+                Ok(model)
+            }) {
+                Ok(model) => model.id,
+                Err(e) => {
+                    eprintln!("WHISPER_MODEL: {e}");
+                    config::DEFAULT_LOCAL_MODEL.to_string()
+                }
+            }
+        }
+        None => config::DEFAULT_LOCAL_MODEL.to_string(),
+    };
+
+    // Forget imported models whose file is gone
+    if let Ok(d) = db.lock() {
+        for model in d.custom_models().unwrap_or_default() {
+            if !model.path.is_file() {
+                eprintln!(
+                    "Imported model {} is gone, forgetting it",
+                    model.path.display()
+                );
+                let _ = d.remove_custom_model(&model.id);
+            }
+        }
+    }
+
     // Determine initial provider: DB setting overrides env var
     let (initial_service, initial_provider, initial_base_url, initial_api_key, initial_api_model) = {
         let db_provider = db
@@ -291,7 +833,7 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
             // Legacy "local" maps to default local model
             Some("local") => (
                 TranscriptionService::Local,
-                config::DEFAULT_LOCAL_MODEL.to_string(),
+                default_local_model.clone(),
                 config.api_base_url.clone(),
                 config.api_key.clone(),
                 config.api_model.clone(),
@@ -343,8 +885,9 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                         key,
                         preset.default_model.to_string(),
                     )
-                } else if config::find_local_model(provider_id).is_some() {
-                    // Local model preset (e.g. "local-base", "local-small")
+                } else if local_model_file(provider_id, &config, &db).is_some() {
+                    // Local model preset (e.g. "local-base", "local-small") or
+                    // imported file ("local:my-model")
                     (
                         TranscriptionService::Local,
                         provider_id.to_string(),
@@ -366,7 +909,7 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
             None => {
                 // No DB setting — use env var config
                 let provider = if config.transcription_service == TranscriptionService::Local {
-                    config::DEFAULT_LOCAL_MODEL
+                    default_local_model.as_str()
                 } else {
                     "groq"
                 };
@@ -381,12 +924,20 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         }
     };
 
+    // Apply the model disk budget, in case it was lowered
+    enforce_model_budget(&config, &db, &[initial_provider.as_str()]);
+
     // Init local whisper only if Local mode AND the selected model file exists
     let initial_whisper: Option<Arc<LocalWhisper>> =
         if initial_service == TranscriptionService::Local {
-            let lm = config::find_local_model(&initial_provider)
-                .unwrap_or(&config::LOCAL_MODEL_PRESETS[0]); // default to "tiny"
-            let model_path = config.models_dir.join(lm.file_name);
+            let model_path = local_model_file(&initial_provider, &config, &db)
+                .map(|(path, _)| path)
+                // default to "tiny"
+                .unwrap_or_else(|| {
+                    config
+                        .models_dir
+                        .join(config::LOCAL_MODEL_PRESETS[0].file_name)
+                });
             if model_path.exists() {
                 match LocalWhisper::new(&model_path) {
                     Ok(w) => Some(Arc::new(w)),
@@ -430,6 +981,65 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         }
     };
 
+    let initial_live_preview = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("live_preview").ok().flatten())
+        .is_some_and(|v| v == "true");
+
+    let setting_on = |key: &str| {
+        db.lock()
+            .ok()
+            .and_then(|d| d.get_setting(key).ok().flatten())
+            .is_some_and(|v| v == "true")
+    };
+    let initial_preprocess = PreprocessOptions {
+        high_pass: setting_on("preprocess_highpass"),
+        denoise: setting_on("preprocess_denoise"),
+        normalize: setting_on("preprocess_normalize"),
+    };
+
+    // Keep compressed recordings with history entries (opt-in). Already kept
+    // ones are pruned at startup even when the option has since been turned off.
+    let initial_retain_audio = setting_on("retain_audio");
+    let retain_max_bytes = config.retain_audio_max_mb as u64 * 1024 * 1024;
+    if let Ok(d) = db.lock()
+        && let Ok(removed) = d.prune_recordings(retain_max_bytes, config.retain_audio_max_days)
+        && removed > 0
+    {
+        dbg_log!("[HISTORY] pruned {removed} retained recordings");
+    }
+
+    // Spoken language: a whisper code, or "auto" to detect it per recording
+    let initial_language = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("language").ok().flatten())
+        .and_then(|v| config::parse_language(&v).ok())
+        .flatten();
+
+    let initial_translate = setting_on("translate");
+    let initial_carry_context = setting_on("carry_context");
+
+    // Decoding preset for local models ("fast", "balanced", "accurate")
+    let initial_decoding_preset = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("decoding_profile").ok().flatten())
+        .filter(|id| config::DecodingProfile::preset(id).is_some())
+        .unwrap_or_else(|| config.decoding_preset.clone());
+    let initial_decoding = config::DecodingProfile::preset(&initial_decoding_preset)
+        .unwrap_or_default()
+        .with_overrides(&config.decoding_overrides);
+
+    // "toggle" (click to start/stop) or "hold" (push-to-talk)
+    let initial_record_mode = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("record_mode").ok().flatten())
+        .filter(|m| m == "hold")
+        .unwrap_or_else(|| "toggle".to_string());
+
     // Runtime state (UI-thread only)
     let runtime = Rc::new(RefCell::new(RuntimeState {
         active_service: initial_service,
@@ -444,11 +1054,67 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         tts_engine: initial_tts_engine,
         tts_downloading: false,
         tts_stop: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        live_preview: initial_live_preview,
+        hold_to_talk: initial_record_mode == "hold",
+        preprocess: initial_preprocess,
+        retain_audio: initial_retain_audio,
+        language: initial_language.clone(),
+        translate: initial_translate,
+        carry_context: initial_carry_context,
+        last_transcript: None,
+        decoding: initial_decoding,
+        active_downloads: Vec::new(),
+        live_preview_worker: None,
+        recording_generation: 0,
     }));
 
+    // Saved audio host (ALSA, JACK, ...); must be set before devices are listed
+    let initial_audio_host = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("audio_host").ok().flatten())
+        .filter(|name| !name.is_empty() && crate::audio::set_audio_host(Some(name)))
+        .unwrap_or_default();
+
+    // Saved input device (None = system default)
+    let initial_input_device = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("input_device").ok().flatten())
+        .filter(|name| !name.is_empty());
+
     // Shared state
     let state = Rc::new(RefCell::new(State::Idle));
-    let recorder = Rc::new(RefCell::new(Recorder::new()));
+    let recorder = Rc::new(RefCell::new(Recorder::with_source(
+        crate::audio_source::from_spec(
+            &config.audio_source,
+            config.raw_pcm,
+            initial_input_device.clone(),
+        ),
+    )));
+
+    // Auto-stop on trailing silence (off unless enabled in the menu)
+    let initial_auto_stop = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("auto_stop").ok().flatten())
+        .is_some_and(|v| v == "true");
+    if initial_auto_stop {
+        recorder
+            .borrow_mut()
+            .set_auto_stop(Some(config.vad_silence_ms));
+    }
+    recorder
+        .borrow_mut()
+        .set_max_duration((config.max_recording_secs > 0).then_some(config.max_recording_secs));
+
+    // Pre-roll keeps the mic open while idle (opt-in, shown by the armed ring)
+    let initial_pre_roll = setting_on("pre_roll");
+    if initial_pre_roll && let Err(e) = recorder.borrow_mut().set_pre_roll(Some(config.pre_roll_ms))
+    {
+        eprintln!("Failed to arm microphone: {e}");
+    }
+    show_armed(&button, recorder.borrow().is_armed());
 
     // --- Left-click handler (on the Button) ---
     let btn = button.clone();
@@ -458,39 +1124,18 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     let config_c = Arc::clone(&config);
     let db_c = Arc::clone(&db);
     let runtime_c = Rc::clone(&runtime);
+    let meter_c = meter.clone();
 
     button.connect_clicked(move |_| {
         let current = *state_c.borrow();
         match current {
             State::Idle => {
-                // Guard: block recording during model download
-                if runtime_c.borrow().downloading {
-                    show_status(&st, "Downloading model...");
-                    return;
-                }
-
-                // Guard: Local mode without loaded model
-                let rt = runtime_c.borrow();
-                if rt.active_service == TranscriptionService::Local && rt.local_whisper.is_none() {
-                    drop(rt);
-                    show_status(&st, "No local model loaded");
+                if let Some(reason) = stt_unavailable(&runtime_c.borrow()) {
+                    show_status(&st, reason);
                     return;
                 }
 
-                // Guard: API mode — check if provider needs key and none is set
-                if rt.active_service == TranscriptionService::Api {
-                    let needs_key = config::find_preset(&rt.active_provider)
-                        .map(|p| p.needs_key)
-                        .unwrap_or(true); // custom defaults to needing a key check
-                    if needs_key && rt.api_key.is_none() {
-                        drop(rt);
-                        show_status(&st, "No API key set");
-                        return;
-                    }
-                }
-                drop(rt);
-
-                if !Recorder::input_available() {
+                if !rec_c.borrow().input_available() {
                     show_status(&st, "No microphone found");
                     return;
                 }
@@ -501,19 +1146,214 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                     return;
                 }
                 *state_c.borrow_mut() = State::Recording;
+                let generation = {
+                    let mut rt = runtime_c.borrow_mut();
+                    rt.recording_generation += 1;
+                    rt.recording_generation
+                };
+                show_armed(&btn, false);
                 btn.add_css_class("recording");
                 btn.remove_css_class("done");
 
                 show_status(&st, "Recording...");
+
+                // Level meter, plus a warning when the input stays at the floor
+                meter_c.set_opacity(1.0);
+                let meter_lvl = meter_c.clone();
+                let st_lvl = st.clone();
+                let state_lvl = Rc::clone(&state_c);
+                let rec_lvl = Rc::clone(&rec_c);
+                let runtime_lvl = Rc::clone(&runtime_c);
+                let mut silent_since: Option<std::time::Instant> = None;
+                let mut warned = false;
+                glib::timeout_add_local(std::time::Duration::from_millis(50), move || {
+                    // A newer recording has its own meter watcher
+                    if runtime_lvl.borrow().recording_generation != generation {
+                        return glib::ControlFlow::Break;
+                    }
+                    let current = *state_lvl.borrow();
+                    if current == State::Paused {
+                        meter_lvl.set_value(0.0);
+                        silent_since = None;
+                        return glib::ControlFlow::Continue;
+                    }
+                    if current != State::Recording {
+                        meter_lvl.set_value(0.0);
+                        meter_lvl.remove_css_class("clipping");
+                        meter_lvl.set_opacity(0.0);
+                        return glib::ControlFlow::Break;
+                    }
+                    let (rms, peak) = rec_lvl.borrow().level();
+                    meter_lvl.set_value(crate::audio::meter_fraction(rms) as f64);
+                    if peak >= 0.99 {
+                        meter_lvl.add_css_class("clipping");
+                    } else {
+                        meter_lvl.remove_css_class("clipping");
+                    }
+
+                    if peak <= crate::audio::SILENCE_FLOOR_RMS {
+                        let since = *silent_since.get_or_insert_with(std::time::Instant::now);
+                        if !warned && since.elapsed() >= SILENT_MIC_WARNING {
+                            show_status(&st_lvl, "Mic seems silent");
+                            warned = true;
+                        }
+                    } else {
+                        silent_since = None;
+                        if warned {
+                            show_status(&st_lvl, "Recording...");
+                            warned = false;
+                        }
+                    }
+                    glib::ControlFlow::Continue
+                });
+
+                // Input health: a file or stdin source running out stops like a
+                // second click; a failed or stalled device keeps what was captured,
+                // switches to the next input, and transcribes
+                let btn_end = btn.clone();
+                let st_end = st.clone();
+                let state_end = Rc::clone(&state_c);
+                let rec_end = Rc::clone(&rec_c);
+                let runtime_end = Rc::clone(&runtime_c);
+                glib::timeout_add_local(std::time::Duration::from_millis(250), move || {
+                    if recording_over(&state_end, &runtime_end, generation) {
+                        return glib::ControlFlow::Break;
+                    }
+                    if rec_end.borrow().input_ended() {
+                        dbg_log!("[AUDIO] end of input");
+                        btn_end.emit_clicked();
+                        return glib::ControlFlow::Break;
+                    }
+                    let Some(error) = rec_end.borrow().stream_error() else {
+                        return glib::ControlFlow::Continue;
+                    };
+
+                    eprintln!("Input lost mid-recording: {error}");
+                    let next = rec_end.borrow_mut().fail_over();
+                    // Stops and transcribes whatever was captured before the failure
+                    btn_end.emit_clicked();
+                    let msg = match next {
+                        Some(name) => {
+                            let name: String = name.chars().take(24).collect();
+                            format!("Mic lost, now using {name}")
+                        }
+                        None => "Mic lost, no other input found".to_string(),
+                    };
+                    // Show the notice for a moment, then put back what the
+                    // stop showed ("Transcribing...") unless it has moved on
+                    let previous = st_end.label();
+                    show_status(&st_end, &msg);
+                    let st_back = st_end.clone();
+                    let state_back = Rc::clone(&state_end);
+                    glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                        if st_back.label() != msg {
+                            return;
+                        }
+                        if *state_back.borrow() == State::Processing {
+                            show_status(&st_back, &previous);
+                        } else {
+                            hide_status(&st_back);
+                        }
+                    });
+                    glib::ControlFlow::Break
+                });
+
+                // Maximum length: count down near the limit, then stop like a second click
+                if let Some(max_secs) = rec_c.borrow().max_duration() {
+                    let btn_max = btn.clone();
+                    let st_max = st.clone();
+                    let state_max = Rc::clone(&state_c);
+                    let rec_max = Rc::clone(&rec_c);
+                    let runtime_max = Rc::clone(&runtime_c);
+                    let mut shown_remaining = None;
+                    glib::timeout_add_local(std::time::Duration::from_millis(250), move || {
+                        if recording_over(&state_max, &runtime_max, generation) {
+                            return glib::ControlFlow::Break;
+                        }
+                        if rec_max.borrow().limit_reached() {
+                            dbg_log!("[AUDIO] max recording length ({max_secs}s) reached");
+                            btn_max.emit_clicked();
+                            return glib::ControlFlow::Break;
+                        }
+                        let elapsed = rec_max.borrow().elapsed_secs();
+                        let remaining = (max_secs as f32 - elapsed).ceil().max(0.0) as u32;
+                        if remaining <= MAX_LENGTH_WARNING_SECS
+                            && shown_remaining != Some(remaining)
+                        {
+                            show_status(&st_max, &format!("Stopping in {remaining}s"));
+                            shown_remaining = Some(remaining);
+                        }
+                        glib::ControlFlow::Continue
+                    });
+                }
+
+                // Auto-stop: watch for trailing silence and stop like a second click
+                if rec_c.borrow().auto_stop_enabled() {
+                    let btn_vad = btn.clone();
+                    let state_vad = Rc::clone(&state_c);
+                    let rec_vad = Rc::clone(&rec_c);
+                    let runtime_vad = Rc::clone(&runtime_c);
+                    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+                        if recording_over(&state_vad, &runtime_vad, generation) {
+                            return glib::ControlFlow::Break;
+                        }
+                        if rec_vad.borrow().silence_reached() {
+                            dbg_log!("[VAD] trailing silence reached, stopping");
+                            btn_vad.emit_clicked();
+                            return glib::ControlFlow::Break;
+                        }
+                        glib::ControlFlow::Continue
+                    });
+                }
+
+                // Live preview: local mode re-transcribes the growing buffer while recording
+                let rt = runtime_c.borrow();
+                if rt.live_preview
+                    && rt.active_service == TranscriptionService::Local
+                    && let Some(whisper) = rt.local_whisper.clone()
+                {
+                    let rec = rec_c.borrow();
+                    let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+                    let (tx, rx) = std::sync::mpsc::channel::<String>();
+                    let worker = spawn_live_preview(
+                        whisper,
+                        rec.sample_buffer(),
+                        rec.channels(),
+                        rec.channel_selection().to_vec(),
+                        rec.sample_rate(),
+                        transcribe_options(&rt, &db_c, true),
+                        Arc::clone(&stop),
+                        tx,
+                    );
+                    drop(rt);
+                    runtime_c.borrow_mut().live_preview_worker = Some((Arc::clone(&stop), worker));
+
+                    let st_prev = st.clone();
+                    let state_prev = Rc::clone(&state_c);
+                    let runtime_prev = Rc::clone(&runtime_c);
+                    glib::timeout_add_local(std::time::Duration::from_millis(200), move || {
+                        if recording_over(&state_prev, &runtime_prev, generation) {
+                            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+                            return glib::ControlFlow::Break;
+                        }
+                        if let Some(text) = rx.try_iter().last() {
+                            show_partial(&st_prev, &text);
+                        }
+                        glib::ControlFlow::Continue
+                    });
+                }
             }
-            State::Recording => {
+            State::Recording | State::Paused => {
                 *state_c.borrow_mut() = State::Processing;
                 btn.remove_css_class("recording");
+                btn.remove_css_class("paused");
                 btn.add_css_class("processing");
 
                 show_status(&st, "Transcribing...");
 
-                let wav = match rec_c.borrow_mut().stop() {
+                let stopped = rec_c.borrow_mut().stop();
+                show_armed(&btn, rec_c.borrow().is_armed());
+                let wav = match stopped {
                     Ok(w) => w,
                     Err(e) => {
                         eprintln!("Record stop error: {e}");
@@ -524,104 +1364,19 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                     }
                 };
 
-                let db_inner = Arc::clone(&db_c);
                 let sample_rate = rec_c.borrow().sample_rate();
-
-                let (tx, rx) = std::sync::mpsc::channel::<Result<String, String>>();
-
-                let rt = runtime_c.borrow();
-                match rt.active_service {
-                    TranscriptionService::Api => {
-                        let base_url = rt.api_base_url.clone();
-                        let api_key = rt.api_key.clone().unwrap_or_default();
-                        let model = rt.api_model.clone();
-                        std::thread::spawn(move || {
-                            let rt = tokio::runtime::Runtime::new()
-                                .expect("failed to create tokio runtime");
-                            let result = rt
-                                .block_on(crate::api::transcribe(&base_url, &api_key, &model, wav));
-                            let _ = tx.send(result);
-                        });
-                    }
-                    TranscriptionService::Local => {
-                        let Some(whisper) = rt.local_whisper.clone() else {
-                            let _ = tx.send(Err("Local model not loaded".into()));
-                            return;
-                        };
-                        std::thread::spawn(move || {
-                            let result = whisper.transcribe(&wav, sample_rate);
-                            let _ = tx.send(result);
-                        });
-                    }
-                }
-                drop(rt);
-
-                let btn2 = btn.clone();
-                let st2 = st.clone();
-                let state_c2 = Rc::clone(&state_c);
-                let notify = config_c.sound_notification;
-                glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-                    match rx.try_recv() {
-                        Ok(Ok(text)) => {
-                            if let Ok(db) = db_inner.lock()
-                                && let Err(e) = db.insert(&text)
-                            {
-                                eprintln!("DB insert error: {e}");
-                            }
-                            match crate::input::copy_to_clipboard(&text) {
-                                Ok(_) => {
-                                    if notify {
-                                        play_notification();
-                                    }
-                                    btn2.remove_css_class("processing");
-                                    btn2.add_css_class("done");
-
-                                    show_status(&st2, "Copied!");
-                                    let st3 = st2.clone();
-                                    let btn3 = btn2.clone();
-                                    glib::timeout_add_local_once(
-                                        std::time::Duration::from_secs(2),
-                                        move || {
-                                            hide_status(&st3);
-                                            btn3.remove_css_class("done");
-                                        },
-                                    );
-                                }
-                                Err(e) => {
-                                    eprintln!("Clipboard error: {e}");
-                                    btn2.remove_css_class("processing");
-
-                                    show_status(&st2, "Error!");
-                                    let st3 = st2.clone();
-                                    glib::timeout_add_local_once(
-                                        std::time::Duration::from_secs(3),
-                                        move || hide_status(&st3),
-                                    );
-                                }
-                            }
-                            *state_c2.borrow_mut() = State::Idle;
-                            glib::ControlFlow::Break
-                        }
-                        Ok(Err(e)) => {
-                            eprintln!("Transcription error: {e}");
-                            btn2.remove_css_class("processing");
-                            show_status(&st2, "Error!");
-                            let st3 = st2.clone();
-                            glib::timeout_add_local_once(
-                                std::time::Duration::from_secs(3),
-                                move || hide_status(&st3),
-                            );
-                            *state_c2.borrow_mut() = State::Idle;
-                            glib::ControlFlow::Break
-                        }
-                        Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
-                        Err(_) => {
-                            *state_c2.borrow_mut() = State::Idle;
-                            btn2.remove_css_class("processing");
-                            glib::ControlFlow::Break
-                        }
-                    }
-                });
+                transcribe_and_copy(
+                    wav,
+                    sample_rate,
+                    None,
+                    None,
+                    &runtime_c,
+                    &db_c,
+                    &state_c,
+                    &btn,
+                    &st,
+                    &config_c,
+                );
             }
             State::Processing | State::Synthesizing => {}
             State::Speaking => {
@@ -654,13 +1409,76 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         Some("app.transcription-mode::custom"),
     );
 
+    // English-only models in the section, multilingual ones in a submenu
     let stt_local_section = gtk4::gio::Menu::new();
+    let multilingual_menu = gtk4::gio::Menu::new();
     for lm in config::LOCAL_MODEL_PRESETS {
-        stt_local_section.append(
+        let target = if lm.multilingual {
+            &multilingual_menu
+        } else {
+            &stt_local_section
+        };
+        target.append(
             Some(&format!("{} ({})", lm.label, lm.size_label)),
             Some(&format!("app.transcription-mode::{}", lm.id)),
         );
     }
+    stt_local_section.append_submenu(Some("Multilingual"), &multilingual_menu);
+
+    // Imported ggml files, then the entry that imports another
+    let imported_models_menu = gtk4::gio::Menu::new();
+    fill_imported_menu(&imported_models_menu, &db);
+    let import_item = gtk4::gio::MenuItem::new(Some("Import Model..."), None);
+    import_item.set_action_and_target_value(Some("app.import-model"), Some(&"".to_variant()));
+    let import_section = gtk4::gio::Menu::new();
+    import_section.append_item(&import_item);
+    let imported_menu = gtk4::gio::Menu::new();
+    imported_menu.append_section(None, &imported_models_menu);
+    imported_menu.append_section(None, &import_section);
+    stt_local_section.append_submenu(Some("Imported"), &imported_menu);
+    stt_local_section.append(Some("Manage Models..."), Some("app.show-models"));
+
+    // Decoding submenu — trades speed for accuracy on local models
+    let decoding_action = gtk4::gio::SimpleAction::new_stateful(
+        "set-decoding-profile",
+        Some(&String::static_variant_type()),
+        &initial_decoding_preset.to_variant(),
+    );
+    let decoding_menu = gtk4::gio::Menu::new();
+    for (id, label) in config::DECODING_PRESETS {
+        decoding_menu.append(
+            Some(label),
+            Some(&format!("app.set-decoding-profile::{id}")),
+        );
+    }
+    stt_local_section.append_submenu(Some("Decoding"), &decoding_menu);
+    stt_local_section.append(Some("Live Preview"), Some("app.live-preview"));
+
+    // Language submenu — "auto" detects the spoken language per recording
+    let language_action = gtk4::gio::SimpleAction::new_stateful(
+        "set-language",
+        Some(&String::static_variant_type()),
+        &initial_language
+            .clone()
+            .unwrap_or_else(|| "auto".to_string())
+            .to_variant(),
+    );
+    let language_menu = gtk4::gio::Menu::new();
+    language_menu.append(Some("Auto-detect"), Some("app.set-language::auto"));
+    for (code, label) in config::LANGUAGE_MENU {
+        language_menu.append(Some(label), Some(&format!("app.set-language::{code}")));
+    }
+    let language_section = gtk4::gio::Menu::new();
+    language_section.append_submenu(Some("Language"), &language_menu);
+    language_section.append(Some("Translate to English"), Some("app.translate"));
+    language_section.append(Some("Glossary..."), Some("app.show-glossary"));
+    language_section.append(Some("Carry Context Forward"), Some("app.carry-context"));
+
+    let live_preview_action = gtk4::gio::SimpleAction::new_stateful(
+        "live-preview",
+        None,
+        &initial_live_preview.to_variant(),
+    );
 
     // TTS section — voice selection
     let tts_initial = if initial_tts_provider == TtsProvider::Piper {
@@ -685,6 +1503,75 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         );
     }
 
+    // Microphone submenu — "" selects the system default device.
+    // A saved device that is currently unplugged shows as the default.
+    let input_device_names = crate::audio::input_devices();
+    let input_initial = initial_input_device
+        .filter(|name| input_device_names.contains(name))
+        .unwrap_or_default();
+    let input_device_action = gtk4::gio::SimpleAction::new_stateful(
+        "set-input-device",
+        Some(&String::static_variant_type()),
+        &input_initial.to_variant(),
+    );
+
+    let mic_menu = gtk4::gio::Menu::new();
+    fill_mic_menu(&mic_menu, &input_device_names);
+
+    // Audio Host submenu — only worth showing when there is a choice
+    let audio_host_action = gtk4::gio::SimpleAction::new_stateful(
+        "set-audio-host",
+        Some(&String::static_variant_type()),
+        &initial_audio_host.to_variant(),
+    );
+    let audio_hosts = crate::audio::audio_hosts();
+    let host_menu = gtk4::gio::Menu::new();
+    host_menu.append(Some("System Default"), Some("app.set-audio-host::"));
+    for name in &audio_hosts {
+        host_menu.append(Some(name), Some(&format!("app.set-audio-host::{name}")));
+    }
+
+    // Input Channels submenu — record one channel or a subset instead of
+    // averaging all of them, remembered per device
+    let initial_channels = load_channel_selection(&db, &input_initial);
+    recorder
+        .borrow_mut()
+        .set_channel_selection(initial_channels.clone());
+    let channels_action = gtk4::gio::SimpleAction::new_stateful(
+        "set-input-channels",
+        Some(&String::static_variant_type()),
+        &crate::audio::format_channel_selection(&initial_channels).to_variant(),
+    );
+    let channel_menu = gtk4::gio::Menu::new();
+    fill_channel_menu(&channel_menu, recorder.borrow().channels());
+
+    let auto_stop_action =
+        gtk4::gio::SimpleAction::new_stateful("auto-stop", None, &initial_auto_stop.to_variant());
+
+    // Audio cleanup stages, applied to the recording before transcription
+    let cleanup_menu = gtk4::gio::Menu::new();
+    cleanup_menu.append(Some("High-pass Filter"), Some("app.high-pass"));
+    cleanup_menu.append(Some("Noise Suppression"), Some("app.denoise"));
+    cleanup_menu.append(Some("Normalize Volume"), Some("app.normalize"));
+
+    let input_section = gtk4::gio::Menu::new();
+    if audio_hosts.len() > 1 {
+        input_section.append_submenu(Some("Audio Host"), &host_menu);
+    }
+    input_section.append_submenu(Some("Microphone"), &mic_menu);
+    input_section.append_submenu(Some("Input Channels"), &channel_menu);
+    input_section.append(Some("Auto-stop on Silence"), Some("app.auto-stop"));
+    input_section.append(Some("Pre-roll (Mic Stays Open)"), Some("app.pre-roll"));
+    input_section.append_submenu(Some("Audio Cleanup"), &cleanup_menu);
+    input_section.append(Some("Click to Toggle"), Some("app.record-mode::toggle"));
+    input_section.append(Some("Hold to Talk"), Some("app.record-mode::hold"));
+
+    let record_mode_action = gtk4::gio::SimpleAction::new_stateful(
+        "record-mode",
+        Some(&String::static_variant_type()),
+        &initial_record_mode.to_variant(),
+    );
+
     let tts_manage = gtk4::gio::Menu::new();
     tts_manage.append(Some("Reset TTS"), Some("app.tts-reset"));
     tts_manage.append(Some("Delete TTS"), Some("app.tts-delete"));
@@ -692,11 +1579,14 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     let actions_section = gtk4::gio::Menu::new();
     actions_section.append(Some("Read Clipboard"), Some("app.read-clipboard"));
     actions_section.append(Some("History"), Some("app.show-history"));
+    actions_section.append(Some("Keep Recordings"), Some("app.retain-audio"));
     actions_section.append(Some("Quit"), Some("app.quit"));
 
     let menu = gtk4::gio::Menu::new();
     menu.append_section(Some("STT — API"), &stt_api_section);
     menu.append_section(Some("STT — Local"), &stt_local_section);
+    menu.append_section(None, &language_section);
+    menu.append_section(None, &input_section);
     menu.append_section(Some("TTS — Voices"), &tts_section);
     menu.append_section(None, &tts_manage);
     menu.append_section(None, &actions_section);
@@ -715,6 +1605,54 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     });
     button.add_controller(gesture);
 
+    // Push-to-talk: in hold mode, press starts and release stops. The drag
+    // gesture runs in the capture phase and claims the sequence, so the
+    // button's own "clicked" never fires; we drive the same handler instead.
+    // A drag (not a click) gesture still reports the release after the
+    // pointer wanders off the button. In toggle mode it is switched off so
+    // it never sees the events, and dragging the window works as before.
+    let hold = gtk4::GestureDrag::new();
+    hold.set_button(1);
+    hold.set_propagation_phase(hold_phase(runtime.borrow().hold_to_talk));
+    let holding = Rc::new(std::cell::Cell::new(false));
+
+    let btn_hold = button.clone();
+    let state_hold = Rc::clone(&state);
+    let runtime_hold = Rc::clone(&runtime);
+    let holding_begin = Rc::clone(&holding);
+    hold.connect_drag_begin(move |g, _, _| {
+        if !runtime_hold.borrow().hold_to_talk {
+            g.set_state(gtk4::EventSequenceState::Denied);
+            return;
+        }
+        g.set_state(gtk4::EventSequenceState::Claimed);
+        // Idle starts recording; Speaking stops TTS like a click would. A
+        // recording that is already running (or paused) is held as it is.
+        if !matches!(*state_hold.borrow(), State::Recording | State::Paused) {
+            btn_hold.emit_clicked();
+        }
+        holding_begin.set(matches!(
+            *state_hold.borrow(),
+            State::Recording | State::Paused
+        ));
+    });
+
+    let btn_release = button.clone();
+    let state_release = Rc::clone(&state);
+    let holding_end = Rc::clone(&holding);
+    let release = move || {
+        // Paused too: letting go still ends the recording
+        if holding_end.replace(false)
+            && matches!(*state_release.borrow(), State::Recording | State::Paused)
+        {
+            btn_release.emit_clicked();
+        }
+    };
+    let release_cancel = release.clone();
+    hold.connect_drag_end(move |_, _, _| release());
+    hold.connect_cancel(move |_, _| release_cancel());
+    button.add_controller(hold.clone());
+
     // Action: transcription mode switch (provider-based)
     let runtime_mode = Rc::clone(&runtime);
     let state_mode = Rc::clone(&state);
@@ -743,24 +1681,20 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
             return;
         }
 
-        if let Some(local_preset) = config::find_local_model(&chosen) {
+        if config::find_local_model(&chosen).is_some()
+            || chosen.starts_with(config::CUSTOM_MODEL_PREFIX)
+        {
             switch_to_local(
+                &win_mode,
                 &runtime_mode,
                 &config_mode,
                 &db_mode,
                 action,
                 &status_mode,
-                local_preset,
+                &chosen,
             );
         } else if chosen == "custom" {
-            show_custom_api_dialog(
-                &win_mode,
-                &runtime_mode,
-                &db_mode,
-                action,
-                &status_mode,
-                &config_mode,
-            );
+            show_custom_api_dialog(&win_mode, &runtime_mode, &db_mode, action, &status_mode);
         } else if let Some(preset) = config::find_preset(&chosen) {
             switch_to_preset(
                 &win_mode,
@@ -775,15 +1709,730 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     });
     app.add_action(&mode_action);
 
+    // Action: import a ggml model file and switch to it (also reachable via
+    // D-Bus with a path). An empty path opens a file chooser.
+    let import_model_action =
+        gtk4::gio::SimpleAction::new("import-model", Some(&String::static_variant_type()));
+    let win_import = window.clone();
+    let db_import = Arc::clone(&db);
+    let status_import = status.clone();
+    let mode_import = mode_action.clone();
+    let menu_import = imported_models_menu.clone();
+    import_model_action.connect_activate(move |_, param| {
+        let Some(path) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        eprintln!("[dbus] 'import-model' action activated");
+        if path.is_empty() {
+            choose_model_file(
+                win_import.upcast_ref(),
+                &db_import,
+                &status_import,
+                &menu_import,
+                &mode_import,
+            );
+        } else {
+            import_model(
+                std::path::Path::new(&path),
+                &db_import,
+                &status_import,
+                &menu_import,
+                &mode_import,
+            );
+        }
+    });
+    app.add_action(&import_model_action);
+
+    // Actions: model manager window, and deleting a model by ID (also
+    // reachable via D-Bus)
+    let models_action = gtk4::gio::SimpleAction::new("show-models", None);
+    let win_models = window.clone();
+    let runtime_models = Rc::clone(&runtime);
+    let config_models = Arc::clone(&config);
+    let db_models = Arc::clone(&db);
+    let status_models = status.clone();
+    let menu_models = imported_models_menu.clone();
+    models_action.connect_activate(move |_, _| {
+        eprintln!("[dbus] 'show-models' action activated");
+        show_model_manager(
+            &win_models,
+            &runtime_models,
+            &config_models,
+            &db_models,
+            &status_models,
+            &menu_models,
+        );
+    });
+    app.add_action(&models_action);
+
+    let delete_model_action =
+        gtk4::gio::SimpleAction::new("delete-model", Some(&String::static_variant_type()));
+    let runtime_del_model = Rc::clone(&runtime);
+    let config_del_model = Arc::clone(&config);
+    let db_del_model = Arc::clone(&db);
+    let status_del_model = status.clone();
+    let menu_del_model = imported_models_menu.clone();
+    delete_model_action.connect_activate(move |_, param| {
+        let Some(id) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        eprintln!("[dbus] 'delete-model' action activated");
+        let label = match delete_local_model(
+            &id,
+            &runtime_del_model,
+            &config_del_model,
+            &db_del_model,
+            &menu_del_model,
+        ) {
+            Ok(()) => "Model deleted".to_string(),
+            Err(e) => {
+                eprintln!("delete-model: {e}");
+                e
+            }
+        };
+        show_status(&status_del_model, &label);
+        let st = status_del_model.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&delete_model_action);
+
+    // Action: cancel every download in progress (also reachable via D-Bus).
+    // Each stops at its next chunk, deletes its .part file and closes its
+    // progress window.
+    let cancel_download_action = gtk4::gio::SimpleAction::new("cancel-download", None);
+    let runtime_cancel_dl = Rc::clone(&runtime);
+    let status_cancel_dl = status.clone();
+    cancel_download_action.connect_activate(move |_, _| {
+        eprintln!("[dbus] 'cancel-download' action activated");
+        let rt = runtime_cancel_dl.borrow();
+        if rt.active_downloads.is_empty() {
+            show_status(&status_cancel_dl, "No download in progress");
+            let st = status_cancel_dl.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
+        }
+        for cancel in &rt.active_downloads {
+            cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    });
+    app.add_action(&cancel_download_action);
+
+    // Action: input device switch (also reachable via D-Bus)
+    let rec_dev = Rc::clone(&recorder);
+    let state_dev = Rc::clone(&state);
+    let db_dev = Arc::clone(&db);
+    let status_dev = status.clone();
+    let btn_dev = button.clone();
+    let channels_dev = channels_action.clone();
+    let channel_menu_dev = channel_menu.clone();
+    input_device_action.connect_activate(move |action, param| {
+        let Some(param) = param else { return };
+        let Some(chosen) = param.get::<String>() else {
+            return;
+        };
+        eprintln!("[dbus] 'set-input-device' action activated");
+
+        // Guard: the stream is bound to a device while recording
+        if *state_dev.borrow() != State::Idle {
+            return;
+        }
+
+        if chosen.len() > 256 {
+            eprintln!("set-input-device: device name too long");
+            return;
+        }
+
+        let device = if chosen.is_empty() {
+            None
+        } else if crate::audio::input_devices().contains(&chosen) {
+            Some(chosen.clone())
+        } else {
+            eprintln!("set-input-device: unknown device {chosen:?}");
+            show_status(&status_dev, "Microphone not found");
+            let st = status_dev.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+                hide_status(&st);
+            });
+            return;
+        };
+
+        rec_dev.borrow_mut().set_device(device);
+        show_armed(&btn_dev, rec_dev.borrow().is_armed());
+
+        // Each device keeps its own channel selection
+        let selection = load_channel_selection(&db_dev, &chosen);
+        channels_dev.set_state(&crate::audio::format_channel_selection(&selection).to_variant());
+        rec_dev.borrow_mut().set_channel_selection(selection);
+        fill_channel_menu(&channel_menu_dev, rec_dev.borrow().channels());
+        if let Ok(d) = db_dev.lock() {
+            let _ = d.set_setting("input_device", &chosen);
+        }
+        action.set_state(&chosen.to_variant());
+
+        let label = if chosen.is_empty() {
+            "Default microphone"
+        } else {
+            "Microphone set"
+        };
+        show_status(&status_dev, label);
+        let st = status_dev.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&input_device_action);
+
+    // Action: audio host switch, e.g. ALSA or JACK (also reachable via D-Bus).
+    // "" selects the platform default.
+    let state_host = Rc::clone(&state);
+    let db_host = Arc::clone(&db);
+    let status_host = status.clone();
+    let mic_menu_host = mic_menu.clone();
+    let device_host = input_device_action.clone();
+    audio_host_action.connect_activate(move |action, param| {
+        let Some(param) = param else { return };
+        let Some(chosen) = param.get::<String>() else {
+            return;
+        };
+        eprintln!("[dbus] 'set-audio-host' action activated");
+
+        // Guard: the running stream belongs to the current host
+        if *state_host.borrow() != State::Idle {
+            return;
+        }
+
+        let name = (!chosen.is_empty()).then_some(chosen.as_str());
+        if !crate::audio::set_audio_host(name) {
+            show_status(&status_host, "Audio host not available");
+            let st = status_host.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+                hide_status(&st);
+            });
+            return;
+        }
+        if let Ok(d) = db_host.lock() {
+            let _ = d.set_setting("audio_host", &chosen);
+        }
+        action.set_state(&chosen.to_variant());
+
+        // Device names differ between hosts: list the new ones and keep the
+        // current device only if the new host has it too
+        let devices = crate::audio::input_devices();
+        fill_mic_menu(&mic_menu_host, &devices);
+        let current = device_host
+            .state()
+            .and_then(|v| v.get::<String>())
+            .filter(|name| devices.contains(name))
+            .unwrap_or_default();
+        device_host.activate(Some(&current.to_variant()));
+
+        let label = if chosen.is_empty() {
+            "Default audio host".to_string()
+        } else {
+            format!("Audio host: {chosen}")
+        };
+        show_status(&status_host, &label);
+        let st = status_host.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&audio_host_action);
+
+    // Action: pick input channels for the current device (also reachable via D-Bus).
+    // Takes "" for all channels, "2" for one, or "1,3" for a subset (1-based).
+    let rec_ch = Rc::clone(&recorder);
+    let state_ch = Rc::clone(&state);
+    let db_ch = Arc::clone(&db);
+    let status_ch = status.clone();
+    let device_ch = input_device_action.clone();
+    channels_action.connect_activate(move |action, param| {
+        let Some(param) = param else { return };
+        let Some(chosen) = param.get::<String>() else {
+            return;
+        };
+        eprintln!("[dbus] 'set-input-channels' action activated");
+
+        // Guard: the channel mix is fixed for the running recording
+        if *state_ch.borrow() != State::Idle {
+            return;
+        }
+
+        let available = rec_ch.borrow().channels();
+        let selection = match crate::audio::parse_channel_selection(&chosen) {
+            Ok(sel) if sel.iter().all(|&c| c < available) => sel,
+            Ok(_) => {
+                let msg = format!("Mic has {available} channel(s)");
+                show_status(&status_ch, &msg);
+                let st = status_ch.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+                    hide_status(&st);
+                });
+                return;
+            }
+            Err(e) => {
+                eprintln!("set-input-channels: {e}");
+                return;
+            }
+        };
+
+        let value = crate::audio::format_channel_selection(&selection);
+        let device = device_ch
+            .state()
+            .and_then(|v| v.get::<String>())
+            .unwrap_or_default();
+        if let Ok(d) = db_ch.lock() {
+            let _ = d.set_setting(&channel_setting_key(&device), &value);
+        }
+        rec_ch.borrow_mut().set_channel_selection(selection);
+        action.set_state(&value.to_variant());
+
+        let label = if value.is_empty() {
+            "All channels".to_string()
+        } else {
+            format!("Channels: {value}")
+        };
+        show_status(&status_ch, &label);
+        let st = status_ch.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&channels_action);
+
+    // Action: toggle auto-stop on silence (also reachable via D-Bus)
+    let rec_vad = Rc::clone(&recorder);
+    let db_vad = Arc::clone(&db);
+    let config_vad = Arc::clone(&config);
+    auto_stop_action.connect_activate(move |action, _| {
+        eprintln!("[dbus] 'auto-stop' action activated");
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        rec_vad
+            .borrow_mut()
+            .set_auto_stop(enabled.then_some(config_vad.vad_silence_ms));
+        if let Ok(d) = db_vad.lock() {
+            let _ = d.set_setting("auto_stop", if enabled { "true" } else { "false" });
+        }
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&auto_stop_action);
+
+    // Action: keep the mic armed with a pre-roll buffer (also reachable via D-Bus)
+    let pre_roll_action =
+        gtk4::gio::SimpleAction::new_stateful("pre-roll", None, &initial_pre_roll.to_variant());
+    let rec_pre = Rc::clone(&recorder);
+    let db_pre = Arc::clone(&db);
+    let config_pre = Arc::clone(&config);
+    let btn_pre = button.clone();
+    let status_pre = status.clone();
+    pre_roll_action.connect_activate(move |action, _| {
+        eprintln!("[dbus] 'pre-roll' action activated");
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        let result = rec_pre
+            .borrow_mut()
+            .set_pre_roll(enabled.then_some(config_pre.pre_roll_ms));
+        show_armed(&btn_pre, rec_pre.borrow().is_armed());
+        if let Err(e) = result {
+            eprintln!("Failed to arm microphone: {e}");
+            rec_pre.borrow_mut().set_pre_roll(None).ok();
+            show_status(&status_pre, &format!("Err: {e}"));
+            let st = status_pre.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
+        }
+        if let Ok(d) = db_pre.lock() {
+            let _ = d.set_setting("pre_roll", if enabled { "true" } else { "false" });
+        }
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&pre_roll_action);
+
+    // Action: toggle live preview of local transcription (also reachable via D-Bus)
+    let runtime_preview = Rc::clone(&runtime);
+    let db_preview = Arc::clone(&db);
+    live_preview_action.connect_activate(move |action, _| {
+        eprintln!("[dbus] 'live-preview' action activated");
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_preview.borrow_mut().live_preview = enabled;
+        if let Ok(d) = db_preview.lock() {
+            let _ = d.set_setting("live_preview", if enabled { "true" } else { "false" });
+        }
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&live_preview_action);
+
+    // Action: spoken language (also reachable via D-Bus). Takes a whisper
+    // code such as "pt" or "de", or "auto" to detect it per recording.
+    let runtime_lang = Rc::clone(&runtime);
+    let db_lang = Arc::clone(&db);
+    let status_lang = status.clone();
+    language_action.connect_activate(move |action, param| {
+        let Some(param) = param else { return };
+        let Some(chosen) = param.get::<String>() else {
+            return;
+        };
+        eprintln!("[dbus] 'set-language' action activated");
+
+        let language = match config::parse_language(&chosen) {
+            Ok(language) => language,
+            Err(e) => {
+                eprintln!("set-language: {e}");
+                return;
+            }
+        };
+        let value = language.clone().unwrap_or_else(|| "auto".to_string());
+        if let Ok(d) = db_lang.lock() {
+            let _ = d.set_setting("language", &value);
+        }
+        action.set_state(&value.to_variant());
+
+        // English-only models ignore the setting, so say so
+        let english_only = {
+            let rt = runtime_lang.borrow();
+            rt.active_service == TranscriptionService::Local
+                && config::find_local_model(&rt.active_provider).is_some_and(|m| !m.multilingual)
+        };
+        runtime_lang.borrow_mut().language = language.clone();
+        let label = match language.as_deref() {
+            Some(code) if code != "en" && english_only => {
+                "English-only model, pick a multilingual one".to_string()
+            }
+            Some(code) => format!("Language: {}", code.to_uppercase()),
+            None => "Language: auto-detect".to_string(),
+        };
+        show_status(&status_lang, &label);
+        let st = status_lang.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&language_action);
+
+    // Action: decoding preset for local models (also reachable via D-Bus).
+    // Values pinned in .env keep applying on top of it.
+    let runtime_dec = Rc::clone(&runtime);
+    let db_dec = Arc::clone(&db);
+    let status_dec = status.clone();
+    let overrides_dec = config.decoding_overrides.clone();
+    decoding_action.connect_activate(move |action, param| {
+        let Some(param) = param else { return };
+        let Some(chosen) = param.get::<String>() else {
+            return;
+        };
+        eprintln!("[dbus] 'set-decoding-profile' action activated");
+
+        let Some(profile) = config::DecodingProfile::preset(&chosen) else {
+            eprintln!("set-decoding-profile: unknown preset {chosen:?}");
+            return;
+        };
+        let profile = profile.with_overrides(&overrides_dec);
+        if let Ok(d) = db_dec.lock() {
+            let _ = d.set_setting("decoding_profile", &chosen);
+        }
+        action.set_state(&chosen.to_variant());
+
+        let label = format!(
+            "Decoding: {chosen} ({} threads{})",
+            profile.threads,
+            if profile.beam_size > 1 {
+                format!(", beam {}", profile.beam_size)
+            } else {
+                String::new()
+            }
+        );
+        runtime_dec.borrow_mut().decoding = profile;
+        show_status(&status_dec, &label);
+        let st = status_dec.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&decoding_action);
+
+    // Action: translate speech to English instead of transcribing it as
+    // spoken (also reachable via D-Bus)
+    let translate_action =
+        gtk4::gio::SimpleAction::new_stateful("translate", None, &initial_translate.to_variant());
+    let runtime_tr = Rc::clone(&runtime);
+    let db_tr = Arc::clone(&db);
+    let status_tr = status.clone();
+    translate_action.connect_activate(move |action, _| {
+        eprintln!("[dbus] 'translate' action activated");
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        if enabled && english_only_local(&runtime_tr.borrow()) {
+            show_status(&status_tr, "English-only model can't translate");
+            let st = status_tr.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
+        }
+        runtime_tr.borrow_mut().translate = enabled;
+        if let Ok(d) = db_tr.lock() {
+            let _ = d.set_setting("translate", if enabled { "true" } else { "false" });
+        }
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&translate_action);
+
+    // Action: use the previous dictation as context for the next one
+    // (also reachable via D-Bus)
+    let carry_action = gtk4::gio::SimpleAction::new_stateful(
+        "carry-context",
+        None,
+        &initial_carry_context.to_variant(),
+    );
+    let runtime_carry = Rc::clone(&runtime);
+    let db_carry = Arc::clone(&db);
+    carry_action.connect_activate(move |action, _| {
+        eprintln!("[dbus] 'carry-context' action activated");
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        {
+            let mut rt = runtime_carry.borrow_mut();
+            rt.carry_context = enabled;
+            rt.last_transcript = None;
+        }
+        if let Ok(d) = db_carry.lock() {
+            let _ = d.set_setting("carry_context", if enabled { "true" } else { "false" });
+        }
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&carry_action);
+
+    // Actions: glossary window, and adding/removing terms (also reachable via D-Bus)
+    let glossary_action = gtk4::gio::SimpleAction::new("show-glossary", None);
+    let db_gloss = Arc::clone(&db);
+    let win_gloss = window.clone();
+    glossary_action.connect_activate(move |_, _| {
+        eprintln!("[dbus] 'show-glossary' action activated");
+        show_glossary_dialog(&win_gloss, &db_gloss);
+    });
+    app.add_action(&glossary_action);
+
+    let glossary_add_action =
+        gtk4::gio::SimpleAction::new("glossary-add", Some(&String::static_variant_type()));
+    let db_gadd = Arc::clone(&db);
+    glossary_add_action.connect_activate(move |_, param| {
+        let Some(term) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        eprintln!("[dbus] 'glossary-add' action activated");
+        if let Ok(d) = db_gadd.lock()
+            && let Err(e) = d.add_glossary_term(&term)
+        {
+            eprintln!("glossary-add: {e}");
+        }
+    });
+    app.add_action(&glossary_add_action);
+
+    let glossary_remove_action =
+        gtk4::gio::SimpleAction::new("glossary-remove", Some(&String::static_variant_type()));
+    let db_grm = Arc::clone(&db);
+    glossary_remove_action.connect_activate(move |_, param| {
+        let Some(term) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        eprintln!("[dbus] 'glossary-remove' action activated");
+        if let Ok(d) = db_grm.lock()
+            && let Err(e) = d.remove_glossary_term(&term)
+        {
+            eprintln!("glossary-remove: {e}");
+        }
+    });
+    app.add_action(&glossary_remove_action);
+
+    // Actions: toggle each audio cleanup stage (also reachable via D-Bus)
+    let cleanup_stages = [
+        (
+            "high-pass",
+            "preprocess_highpass",
+            initial_preprocess.high_pass,
+        ),
+        ("denoise", "preprocess_denoise", initial_preprocess.denoise),
+        (
+            "normalize",
+            "preprocess_normalize",
+            initial_preprocess.normalize,
+        ),
+    ];
+    for (name, key, initial) in cleanup_stages {
+        let action = gtk4::gio::SimpleAction::new_stateful(name, None, &initial.to_variant());
+        let runtime_pp = Rc::clone(&runtime);
+        let db_pp = Arc::clone(&db);
+        action.connect_activate(move |action, _| {
+            eprintln!("[dbus] '{name}' action activated");
+            let enabled = !action
+                .state()
+                .and_then(|v| v.get::<bool>())
+                .unwrap_or(false);
+            {
+                let mut rt = runtime_pp.borrow_mut();
+                match name {
+                    "high-pass" => rt.preprocess.high_pass = enabled,
+                    "denoise" => rt.preprocess.denoise = enabled,
+                    _ => rt.preprocess.normalize = enabled,
+                }
+            }
+            if let Ok(d) = db_pp.lock() {
+                let _ = d.set_setting(key, if enabled { "true" } else { "false" });
+            }
+            action.set_state(&enabled.to_variant());
+        });
+        app.add_action(&action);
+    }
+
+    // Action: record mode (toggle / hold), persisted
+    let runtime_rm = Rc::clone(&runtime);
+    let db_rm = Arc::clone(&db);
+    let hold_rm = hold.clone();
+    record_mode_action.connect_activate(move |action, param| {
+        let Some(param) = param else { return };
+        let Some(chosen) = param.get::<String>() else {
+            return;
+        };
+        eprintln!("[dbus] 'record-mode' action activated");
+        if chosen != "toggle" && chosen != "hold" {
+            eprintln!("record-mode: expected 'toggle' or 'hold'");
+            return;
+        }
+        runtime_rm.borrow_mut().hold_to_talk = chosen == "hold";
+        hold_rm.set_propagation_phase(hold_phase(chosen == "hold"));
+        if let Ok(d) = db_rm.lock() {
+            let _ = d.set_setting("record_mode", &chosen);
+        }
+        action.set_state(&chosen.to_variant());
+    });
+    app.add_action(&record_mode_action);
+
+    // Action: keep recordings with history entries (also reachable via D-Bus)
+    let retain_action = gtk4::gio::SimpleAction::new_stateful(
+        "retain-audio",
+        None,
+        &initial_retain_audio.to_variant(),
+    );
+    let runtime_retain = Rc::clone(&runtime);
+    let db_retain = Arc::clone(&db);
+    retain_action.connect_activate(move |action, _| {
+        eprintln!("[dbus] 'retain-audio' action activated");
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_retain.borrow_mut().retain_audio = enabled;
+        if let Ok(d) = db_retain.lock() {
+            let _ = d.set_setting("retain_audio", if enabled { "true" } else { "false" });
+        }
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&retain_action);
+
+    // Re-transcribe a retained recording with the current provider,
+    // replacing the text of its history entry
+    let btn_re = button.clone();
+    let st_re = status.clone();
+    let state_re = Rc::clone(&state);
+    let runtime_re = Rc::clone(&runtime);
+    let db_re = Arc::clone(&db);
+    let config_re = Arc::clone(&config);
+    let retranscribe: Rc<dyn Fn(i64)> = Rc::new(move |id| {
+        if *state_re.borrow() != State::Idle {
+            show_status(&st_re, "Busy");
+            return;
+        }
+        if let Some(reason) = stt_unavailable(&runtime_re.borrow()) {
+            show_status(&st_re, reason);
+            return;
+        }
+        let stored = db_re
+            .lock()
+            .ok()
+            .and_then(|d| d.recording(id).ok().flatten());
+        let Some(wav) = stored.and_then(|audio| {
+            crate::retention::decompress_to_wav(&audio)
+                .and_then(|wav| crate::audio::wav_file(&wav))
+                .map_err(|e| eprintln!("Re-transcribe error: {e}"))
+                .ok()
+        }) else {
+            show_status(&st_re, "Recording unavailable");
+            return;
+        };
+
+        *state_re.borrow_mut() = State::Processing;
+        btn_re.remove_css_class("done");
+        btn_re.add_css_class("processing");
+        show_status(&st_re, "Transcribing...");
+        transcribe_and_copy(
+            wav,
+            crate::retention::RETAINED_SAMPLE_RATE,
+            Some(id),
+            None,
+            &runtime_re,
+            &db_re,
+            &state_re,
+            &btn_re,
+            &st_re,
+            &config_re,
+        );
+    });
+
     // Action: show history
     let history_action = gtk4::gio::SimpleAction::new("show-history", None);
     let db_hist = Arc::clone(&db);
     let win_ref = window.clone();
     history_action.connect_activate(move |_, _| {
-        show_history_dialog(&win_ref, &db_hist);
+        show_history_dialog(&win_ref, &db_hist, Rc::clone(&retranscribe));
     });
     app.add_action(&history_action);
 
+    // Action: export subtitles (SRT or WebVTT, by extension) of a history
+    // entry to a path, given as "<id>:<path>" (D-Bus only)
+    let export_action =
+        gtk4::gio::SimpleAction::new("export-subtitles", Some(&String::static_variant_type()));
+    let db_export = Arc::clone(&db);
+    let status_export = status.clone();
+    export_action.connect_activate(move |_, param| {
+        let Some(request) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        eprintln!("[dbus] 'export-subtitles' action activated");
+        let result = crate::transcript::parse_export_request(&request)
+            .and_then(|(id, path)| export_subtitles(&db_export, id, std::path::Path::new(path)));
+        let label = match result {
+            Ok(()) => "Subtitles saved",
+            Err(e) => {
+                eprintln!("export-subtitles: {e}");
+                "Export failed"
+            }
+        };
+        show_status(&status_export, label);
+        let st = status_export.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&export_action);
+
     // Action: quit
     let quit_action = gtk4::gio::SimpleAction::new("quit", None);
     quit_action.connect_activate(move |_, _| {
@@ -819,7 +2468,7 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     let esc_shortcut = gtk4::Shortcut::new(
         gtk4::ShortcutTrigger::parse_string("Escape"),
         Some(gtk4::CallbackAction::new(move |_, _| {
-            if *esc_state.borrow() == State::Recording {
+            if matches!(*esc_state.borrow(), State::Recording | State::Paused) {
                 esc_btn.emit_clicked();
             }
             glib::Propagation::Stop
@@ -853,30 +2502,203 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                 .stderr(std::process::Stdio::null())
                 .spawn();
         }
-        if *state_rec.borrow() == State::Idle {
-            btn_rec.emit_clicked();
+        if *state_rec.borrow() == State::Idle {
+            btn_rec.emit_clicked();
+        }
+    });
+    app.add_action(&record_action);
+
+    // --- D-Bus action: "stop" — triggered by GNOME shortcut ---
+    let stop_action = gtk4::gio::SimpleAction::new("stop", None);
+    let btn_stop = button.clone();
+    let state_stop = Rc::clone(&state);
+    stop_action.connect_activate(move |_, _| {
+        eprintln!("[dbus] 'stop' action activated");
+        if matches!(*state_stop.borrow(), State::Recording | State::Paused) {
+            btn_stop.emit_clicked();
+        }
+    });
+    app.add_action(&stop_action);
+
+    // --- D-Bus action: "pause" — pause / resume the current recording ---
+    let pause_action = gtk4::gio::SimpleAction::new("pause", None);
+    let btn_pause = button.clone();
+    let st_pause = status.clone();
+    let state_pause = Rc::clone(&state);
+    let rec_pause = Rc::clone(&recorder);
+    pause_action.connect_activate(move |_, _| {
+        eprintln!("[dbus] 'pause' action activated");
+        let current = *state_pause.borrow();
+        match current {
+            State::Recording => {
+                rec_pause.borrow_mut().pause();
+                *state_pause.borrow_mut() = State::Paused;
+                btn_pause.remove_css_class("recording");
+                btn_pause.add_css_class("paused");
+                show_status(&st_pause, "Paused");
+            }
+            State::Paused => {
+                if let Err(e) = rec_pause.borrow_mut().resume() {
+                    // Still paused: a click transcribes what was captured so far
+                    eprintln!("Record resume error: {e}");
+                    show_status(&st_pause, &format!("Err: {e}"));
+                    return;
+                }
+                *state_pause.borrow_mut() = State::Recording;
+                btn_pause.remove_css_class("paused");
+                btn_pause.add_css_class("recording");
+                show_status(&st_pause, "Recording...");
+            }
+            _ => {}
+        }
+    });
+    app.add_action(&pause_action);
+
+    // Middle-click on the button pauses / resumes
+    let pause_ref = pause_action.clone();
+    let middle = gtk4::GestureClick::new();
+    middle.set_button(2);
+    middle.connect_pressed(move |g, _, _, _| {
+        g.set_state(gtk4::EventSequenceState::Claimed);
+        pause_ref.activate(None);
+    });
+    button.add_controller(middle);
+
+    // --- D-Bus actions: "record-start" / "record-end" — push-to-talk key bindings ---
+    // Bind record-start to key-down and record-end to key-up.
+    let record_start_action = gtk4::gio::SimpleAction::new("record-start", None);
+    let record_ref = record_action.clone();
+    record_start_action.connect_activate(move |_, _| {
+        eprintln!("[dbus] 'record-start' action activated");
+        record_ref.activate(None);
+    });
+    app.add_action(&record_start_action);
+
+    let record_end_action = gtk4::gio::SimpleAction::new("record-end", None);
+    let stop_ref = stop_action.clone();
+    record_end_action.connect_activate(move |_, _| {
+        eprintln!("[dbus] 'record-end' action activated");
+        stop_ref.activate(None);
+    });
+    app.add_action(&record_end_action);
+
+    // --- D-Bus action: "transcribe-file" — transcribe an existing audio file ---
+    // Also used by drag-and-drop onto the button and by paths on the command line.
+    let transcribe_file_action =
+        gtk4::gio::SimpleAction::new("transcribe-file", Some(&String::static_variant_type()));
+    let btn_file = button.clone();
+    let st_file = status.clone();
+    let state_file = Rc::clone(&state);
+    let runtime_file = Rc::clone(&runtime);
+    let db_file = Arc::clone(&db);
+    let config_file = Arc::clone(&config);
+    transcribe_file_action.connect_activate(move |_, param| {
+        let Some(request) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        eprintln!("[dbus] 'transcribe-file' action activated");
+        let (path, subtitles) = crate::audio_file::parse_file_request(&request);
+
+        if *state_file.borrow() != State::Idle {
+            show_status(&st_file, "Busy");
+            return;
+        }
+        if let Some(reason) = stt_unavailable(&runtime_file.borrow()) {
+            show_status(&st_file, reason);
+            return;
+        }
+        if !path.is_file() || !crate::audio_file::is_supported(&path) {
+            eprintln!(
+                "transcribe-file: not a supported audio file: {}",
+                path.display()
+            );
+            show_status(&st_file, "Unsupported file");
+            let st = st_file.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
         }
-    });
-    app.add_action(&record_action);
 
-    // --- D-Bus action: "stop" — triggered by GNOME shortcut ---
-    let stop_action = gtk4::gio::SimpleAction::new("stop", None);
-    let btn_stop = button.clone();
-    let state_stop = Rc::clone(&state);
-    stop_action.connect_activate(move |_, _| {
-        eprintln!("[dbus] 'stop' action activated");
-        if *state_stop.borrow() == State::Recording {
-            btn_stop.emit_clicked();
-        }
+        *state_file.borrow_mut() = State::Processing;
+        btn_file.remove_css_class("done");
+        btn_file.add_css_class("processing");
+        show_status(&st_file, "Decoding file...");
+
+        let (tx, rx) = std::sync::mpsc::channel::<Result<std::fs::File, String>>();
+        std::thread::spawn(move || {
+            let _ = tx.send(
+                crate::audio_file::file_to_wav(&path).and_then(|wav| crate::audio::wav_file(&wav)),
+            );
+        });
+
+        let btn = btn_file.clone();
+        let st = st_file.clone();
+        let state_c = Rc::clone(&state_file);
+        let runtime_c = Rc::clone(&runtime_file);
+        let db_c = Arc::clone(&db_file);
+        let config_c = Arc::clone(&config_file);
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            match rx.try_recv() {
+                Ok(Ok(wav)) => {
+                    show_status(&st, "Transcribing...");
+                    transcribe_and_copy(
+                        wav,
+                        crate::audio_file::FILE_SAMPLE_RATE,
+                        None,
+                        subtitles.clone(),
+                        &runtime_c,
+                        &db_c,
+                        &state_c,
+                        &btn,
+                        &st,
+                        &config_c,
+                    );
+                    glib::ControlFlow::Break
+                }
+                Ok(Err(e)) => {
+                    eprintln!("File decode error: {e}");
+                    btn.remove_css_class("processing");
+                    show_status(&st, "Error!");
+                    let st2 = st.clone();
+                    glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                        hide_status(&st2)
+                    });
+                    *state_c.borrow_mut() = State::Idle;
+                    glib::ControlFlow::Break
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(_) => {
+                    *state_c.borrow_mut() = State::Idle;
+                    btn.remove_css_class("processing");
+                    glib::ControlFlow::Break
+                }
+            }
+        });
     });
-    app.add_action(&stop_action);
+    app.add_action(&transcribe_file_action);
+
+    // Drag-and-drop an audio file onto the button to transcribe it
+    let drop_target = gtk4::DropTarget::new(gdk::FileList::static_type(), gdk::DragAction::COPY);
+    let file_ref = transcribe_file_action.clone();
+    drop_target.connect_drop(move |_, value, _, _| {
+        let Some(path) = value
+            .get::<gdk::FileList>()
+            .ok()
+            .and_then(|list| list.files().first().and_then(|f| f.path()))
+        else {
+            return false;
+        };
+        file_ref.activate(Some(&path.to_string_lossy().to_variant()));
+        true
+    });
+    button.add_controller(drop_target);
 
     // --- D-Bus action: "set-api-config" — programmatic custom API setup ---
     let api_config_action =
         gtk4::gio::SimpleAction::new("set-api-config", Some(&String::static_variant_type()));
     let runtime_api_cfg = Rc::clone(&runtime);
     let db_api_cfg = Arc::clone(&db);
-    let config_api_cfg = Arc::clone(&config);
     let mode_action_ref = mode_action.clone();
     api_config_action.connect_activate(move |_, param| {
         let Some(param) = param else { return };
@@ -939,9 +2761,6 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
             rt.local_whisper = None;
         }
 
-        // Delete model file to free disk space
-        delete_all_local_models(&config_api_cfg.models_dir);
-
         mode_action_ref.set_state(&"custom".to_variant());
     });
     app.add_action(&api_config_action);
@@ -1026,7 +2845,7 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
             dbg_log!("[TTS] downloading voice {}...", voice.id);
             download_tts_models(
                 &runtime_tts,
-                &config_tts.models_dir,
+                &config_tts,
                 &db_tts,
                 action,
                 &read_cb_action_ref,
@@ -1266,17 +3085,6 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     window.present();
 }
 
-fn delete_all_local_models(models_dir: &std::path::Path) {
-    for lm in config::LOCAL_MODEL_PRESETS {
-        let path = models_dir.join(lm.file_name);
-        if path.exists()
-            && let Err(e) = std::fs::remove_file(&path)
-        {
-            eprintln!("Failed to delete model file {}: {e}", lm.file_name);
-        }
-    }
-}
-
 fn switch_to_preset(
     parent: &gtk4::ApplicationWindow,
     runtime: &Rc<RefCell<RuntimeState>>,
@@ -1302,16 +3110,15 @@ fn switch_to_preset(
 
     // If provider needs a key and we don't have one, show a dialog to collect it
     if preset.needs_key && resolved_key.is_none() {
-        show_api_key_dialog(parent, runtime, config, db, action, status, preset);
+        show_api_key_dialog(parent, runtime, db, action, status, preset);
         return;
     }
 
-    apply_preset(runtime, config, db, action, status, preset, resolved_key);
+    apply_preset(runtime, db, action, status, preset, resolved_key);
 }
 
 fn apply_preset(
     runtime: &Rc<RefCell<RuntimeState>>,
-    config: &Arc<Config>,
     db: &Arc<Mutex<Db>>,
     action: &gtk4::gio::SimpleAction,
     status: &gtk4::Label,
@@ -1328,9 +3135,6 @@ fn apply_preset(
         rt.local_whisper = None;
     }
 
-    // Delete all local model files to free disk space
-    delete_all_local_models(&config.models_dir);
-
     // Persist to DB
     if let Ok(d) = db.lock() {
         let _ = d.set_setting("transcription_mode", preset.id);
@@ -1348,7 +3152,6 @@ fn apply_preset(
 fn show_api_key_dialog(
     parent: &gtk4::ApplicationWindow,
     runtime: &Rc<RefCell<RuntimeState>>,
-    config: &Arc<Config>,
     db: &Arc<Mutex<Db>>,
     action: &gtk4::gio::SimpleAction,
     status: &gtk4::Label,
@@ -1405,7 +3208,6 @@ fn show_api_key_dialog(
 
     // Save → persist key to DB, then switch
     let runtime_save = Rc::clone(runtime);
-    let config_save = Arc::clone(config);
     let db_save = Arc::clone(db);
     let action_save = action.clone();
     let status_save = status.clone();
@@ -1435,7 +3237,6 @@ fn show_api_key_dialog(
 
         apply_preset(
             &runtime_save,
-            &config_save,
             &db_save,
             &action_save,
             &status_save,
@@ -1455,7 +3256,6 @@ fn show_custom_api_dialog(
     db: &Arc<Mutex<Db>>,
     action: &gtk4::gio::SimpleAction,
     status: &gtk4::Label,
-    config: &Arc<Config>,
 ) {
     let previous_provider = runtime.borrow().active_provider.clone();
 
@@ -1541,7 +3341,6 @@ fn show_custom_api_dialog(
     // Save → persist + switch
     let runtime_save = Rc::clone(runtime);
     let db_save = Arc::clone(db);
-    let config_save = Arc::clone(config);
     let action_save = action.clone();
     let status_save = status.clone();
     let dialog_save = dialog.clone();
@@ -1581,9 +3380,6 @@ fn show_custom_api_dialog(
             rt.local_whisper = None;
         }
 
-        // Delete model file to free disk space
-        delete_all_local_models(&config_save.models_dir);
-
         action_save.set_state(&"custom".to_variant());
 
         show_status(&status_save, "Custom API mode");
@@ -1598,53 +3394,480 @@ fn show_custom_api_dialog(
     dialog.present();
 }
 
+/// File of a local model (a preset in the models directory, or an imported
+/// file), with its download URL and checksum for presets. `None` for
+/// unknown IDs.
+fn local_model_file(
+    id: &str,
+    config: &Config,
+    db: &Arc<Mutex<Db>>,
+) -> Option<(
+    std::path::PathBuf,
+    Option<(String, crate::download::Checksum)>,
+)> {
+    if let Some(preset) = config::find_local_model(id) {
+        return Some((
+            config.models_dir.join(preset.file_name),
+            Some((preset.url(&config.hf_endpoint), preset_checksum(preset))),
+        ));
+    }
+    db.lock()
+        .ok()?
+        .custom_models()
+        .ok()?
+        .into_iter()
+        .find(|m| m.id == id)
+        .map(|m| (m.path, None))
+}
+
 fn switch_to_local(
+    window: &gtk4::ApplicationWindow,
     runtime: &Rc<RefCell<RuntimeState>>,
     config: &Arc<Config>,
     db: &Arc<Mutex<Db>>,
     action: &gtk4::gio::SimpleAction,
     status: &gtk4::Label,
-    local_preset: &config::LocalModelPreset,
+    model_id: &str,
 ) {
-    // Delete any previously loaded model files from other presets
-    {
-        let rt = runtime.borrow();
-        if rt.active_service == TranscriptionService::Local
-            && let Some(old_model) = config::find_local_model(&rt.active_provider)
-            && old_model.id != local_preset.id
-        {
-            let old_path = config.models_dir.join(old_model.file_name);
-            if old_path.exists()
-                && let Err(e) = std::fs::remove_file(&old_path)
-            {
-                eprintln!("Failed to delete old model file: {e}");
-            }
-        }
-    }
+    let Some((model_path, source)) = local_model_file(model_id, config, db) else {
+        eprintln!("Unknown local model: {model_id}");
+        return;
+    };
 
     // Set active service immediately so the menu reflects the choice
     {
         let mut rt = runtime.borrow_mut();
         rt.active_service = TranscriptionService::Local;
-        rt.active_provider = local_preset.id.to_string();
+        rt.active_provider = model_id.to_string();
         rt.local_whisper = None;
     }
-    action.set_state(&local_preset.id.to_variant());
+    action.set_state(&model_id.to_variant());
 
     // Persist to DB
     if let Ok(d) = db.lock() {
-        let _ = d.set_setting("transcription_mode", local_preset.id);
+        let _ = d.set_setting("transcription_mode", model_id);
+        let _ = d.touch_model(model_id);
     }
+    enforce_model_budget(config, db, &[model_id]);
+
+    match source {
+        Some((url, checksum)) if !model_path.exists() => {
+            download_and_load_model(
+                window,
+                runtime,
+                config,
+                db,
+                &model_path,
+                &url,
+                checksum,
+                action,
+                status,
+            );
+        }
+        // An imported file that has gone missing fails to load like a bad one
+        _ => load_whisper_model(runtime, &model_path, action, status),
+    }
+}
+
+/// Import a ggml file as a local model, list it in the Imported menu and
+/// switch to it.
+fn import_model(
+    path: &std::path::Path,
+    db: &Arc<Mutex<Db>>,
+    status: &gtk4::Label,
+    menu: &gtk4::gio::Menu,
+    mode_action: &gtk4::gio::SimpleAction,
+) {
+    let imported = config::CustomModel::from_path(path).and_then(|model| {
+        let db = db.lock().map_err(|e| e.to_string())?;
+        let model = model.unique_among(&db.custom_models().map_err(|e| e.to_string())?);
+        db.add_custom_model(&model).map_err(|e| e.to_string())?;
+        Ok(model)
+    });
+    let model = match imported {
+        Ok(imported) => imported,
+        Err(e) => {
+            eprintln!("import-model: {e}");
+            show_status(status, "Not a whisper model");
+            let st = status.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
+        }
+    };
+    fill_imported_menu(menu, db);
+    mode_action.activate(Some(&model.id.to_variant()));
+}
+
+/// Refill the Imported submenu from the database.
+fn fill_imported_menu(menu: &gtk4::gio::Menu, db: &Arc<Mutex<Db>>) {
+    menu.remove_all();
+    let models = db
+        .lock()
+        .ok()
+        .and_then(|d| d.custom_models().ok())
+        .unwrap_or_default();
+    for model in models {
+        menu.append(
+            Some(&model.label),
+            Some(&format!("app.transcription-mode::{}", model.id)),
+        );
+    }
+}
+
+/// Delete the least recently used downloaded models until the rest fit in
+/// the `MODELS_MAX_MB` budget. The models in `keep` stay.
+fn enforce_model_budget(config: &Config, db: &Arc<Mutex<Db>>, keep: &[&str]) {
+    if config.models_max_mb == 0 {
+        return;
+    }
+    let Ok(d) = db.lock() else { return };
+    let installed = crate::models::installed_models(
+        &config.models_dir,
+        &d.custom_models().unwrap_or_default(),
+        &d.model_usage().unwrap_or_default(),
+    );
+    let max_bytes = config.models_max_mb as u64 * 1024 * 1024;
+    for model in crate::models::lru_evictions(&installed, max_bytes, keep) {
+        match std::fs::remove_file(&model.path) {
+            Ok(()) => dbg_log!("[MODELS] evicted {} to stay in budget", model.id),
+            Err(e) => eprintln!("Failed to delete model file {}: {e}", model.path.display()),
+        }
+    }
+}
 
-    let model_path = config.models_dir.join(local_preset.file_name);
-    if model_path.exists() {
-        load_whisper_model(runtime, &model_path, action, status);
+/// Delete a downloaded model's file, or forget an imported one (its file
+/// is left alone). The active model can't be removed.
+fn delete_local_model(
+    id: &str,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Config,
+    db: &Arc<Mutex<Db>>,
+    imported_menu: &gtk4::gio::Menu,
+) -> Result<(), String> {
+    {
+        let rt = runtime.borrow();
+        if rt.active_service == TranscriptionService::Local && rt.active_provider == id {
+            return Err("Model in use".into());
+        }
+    }
+    if let Some(preset) = config::find_local_model(id) {
+        let path = config.models_dir.join(preset.file_name);
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete {}: {e}", path.display()))
+    } else if id.starts_with(config::CUSTOM_MODEL_PREFIX) {
+        let removed = db
+            .lock()
+            .map_err(|e| e.to_string())?
+            .remove_custom_model(id)
+            .map_err(|e| e.to_string())?;
+        fill_imported_menu(imported_menu, db);
+        if removed {
+            Ok(())
+        } else {
+            Err(format!("Unknown model: {id}"))
+        }
     } else {
-        let url = config::model_url(local_preset.file_name);
-        download_and_load_model(runtime, &model_path, &url, action, status);
+        Err(format!("Unknown model: {id}"))
+    }
+}
+
+/// Download a preset without switching to it, then run `on_done`.
+fn download_model_only(
+    parent: &gtk4::Window,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    preset: &'static config::LocalModelPreset,
+    status: &gtk4::Label,
+    on_done: impl Fn() + 'static,
+) {
+    if runtime.borrow().downloading {
+        return;
+    }
+    runtime.borrow_mut().downloading = true;
+    show_status(status, "Downloading model...");
+    let cancel = start_download(runtime);
+    let dialog = DownloadDialog::new(
+        parent,
+        "Downloading Model",
+        &format!("Downloading {} ({})...", preset.label, preset.size_label),
+        &cancel,
+    );
+    let rx = spawn_model_download(
+        &preset.url(&config.hf_endpoint),
+        &config.models_dir.join(preset.file_name),
+        preset_checksum(preset),
+        &cancel,
+    );
+
+    let runtime_c = Rc::clone(runtime);
+    let config_c = Arc::clone(config);
+    let db_c = Arc::clone(db);
+    let st = status.clone();
+    watch_download(runtime, cancel, rx, dialog, move |end| {
+        let label = match end {
+            DownloadEnd::Done => {
+                let active = runtime_c.borrow().active_provider.clone();
+                enforce_model_budget(&config_c, &db_c, &[active.as_str(), preset.id]);
+                "Model downloaded"
+            }
+            DownloadEnd::Cancelled => "Download cancelled",
+            DownloadEnd::Failed(e) => {
+                eprintln!("Model download failed: {e}");
+                "Download failed"
+            }
+        };
+        runtime_c.borrow_mut().downloading = false;
+        show_status(&st, label);
+        let st2 = st.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+            hide_status(&st2);
+        });
+        on_done();
+    });
+}
+
+/// Model manager window: every preset and imported model, with its size,
+/// when it was last used, and a button to delete or download it.
+fn show_model_manager(
+    window: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    status: &gtk4::Label,
+    imported_menu: &gtk4::gio::Menu,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("WhisperCrabs Models")
+        .transient_for(window)
+        .default_width(420)
+        .default_height(480)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let header = gtk4::Label::new(Some("Models"));
+    header.add_css_class("heading");
+    vbox.append(&header);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    let list_box = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+    fill_model_list(&list_box, runtime, config, db, status, imported_menu);
+    scroll.set_child(Some(&list_box));
+    vbox.append(&scroll);
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Refill the model manager's list.
+fn fill_model_list(
+    list_box: &gtk4::Box,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    status: &gtk4::Label,
+    imported_menu: &gtk4::gio::Menu,
+) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+    let (imported, usage) = db
+        .lock()
+        .ok()
+        .map(|d| {
+            (
+                d.custom_models().unwrap_or_default(),
+                d.model_usage().unwrap_or_default(),
+            )
+        })
+        .unwrap_or_default();
+    let installed = crate::models::installed_models(&config.models_dir, &imported, &usage);
+
+    let downloaded: u64 = installed
+        .iter()
+        .filter(|m| !m.imported)
+        .map(|m| m.size)
+        .sum();
+    let summary = match config.models_max_mb {
+        0 => format!(
+            "Downloaded models use {}. They stay on disk until deleted here.",
+            crate::models::format_size(downloaded)
+        ),
+        max_mb => format!(
+            "Downloaded models use {} of the {} budget. Past it, the least recently used are deleted.",
+            crate::models::format_size(downloaded),
+            crate::models::format_size(max_mb as u64 * 1024 * 1024)
+        ),
+    };
+    let summary = gtk4::Label::new(Some(&summary));
+    summary.set_wrap(true);
+    summary.set_opacity(0.6);
+    list_box.append(&summary);
+
+    let (active_id, downloading) = {
+        let rt = runtime.borrow();
+        let active =
+            (rt.active_service == TranscriptionService::Local).then(|| rt.active_provider.clone());
+        (active, rt.downloading)
+    };
+
+    // Presets in menu order, then imported files
+    let presets = config::LOCAL_MODEL_PRESETS.iter().map(|p| (p.id, Some(p)));
+    let imported_ids = imported
+        .iter()
+        .filter(|m| installed.iter().any(|i| i.id == m.id))
+        .map(|m| (m.id.as_str(), None));
+    for (id, preset) in presets.chain(imported_ids) {
+        let model = installed.iter().find(|m| m.id == id);
+        let title = match (model, preset) {
+            (Some(model), _) => model.label.clone(),
+            (None, Some(p)) if p.multilingual => format!("{} (multilingual)", p.label),
+            (None, Some(p)) => p.label.to_string(),
+            (None, None) => continue,
+        };
+        let detail = match (model, preset) {
+            (Some(model), _) => format!(
+                "{} · {}",
+                crate::models::format_size(model.size),
+                model
+                    .last_used
+                    .as_deref()
+                    .map_or("never used".to_string(), |at| format!("used {at}"))
+            ),
+            (None, Some(p)) => format!("{} · not downloaded", p.size_label),
+            (None, None) => continue,
+        };
+
+        let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        let text = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+        text.set_hexpand(true);
+        let title = gtk4::Label::new(Some(&title));
+        title.set_halign(gtk4::Align::Start);
+        let detail = gtk4::Label::new(Some(&detail));
+        detail.set_halign(gtk4::Align::Start);
+        detail.set_opacity(0.6);
+        text.append(&title);
+        text.append(&detail);
+        row.append(&text);
+
+        let in_use = active_id.as_deref() == Some(id);
+        let button = match (model, preset) {
+            _ if in_use => {
+                let b = gtk4::Button::with_label("In use");
+                b.set_sensitive(false);
+                b
+            }
+            (Some(model), _) => {
+                let b = gtk4::Button::with_label(if model.imported { "Forget" } else { "Delete" });
+                let id = id.to_string();
+                let list_c = list_box.clone();
+                let runtime_c = Rc::clone(runtime);
+                let config_c = Arc::clone(config);
+                let db_c = Arc::clone(db);
+                let status_c = status.clone();
+                let menu_c = imported_menu.clone();
+                b.connect_clicked(move |_| {
+                    if let Err(e) = delete_local_model(&id, &runtime_c, &config_c, &db_c, &menu_c) {
+                        eprintln!("Model delete error: {e}");
+                    }
+                    fill_model_list(&list_c, &runtime_c, &config_c, &db_c, &status_c, &menu_c);
+                });
+                b
+            }
+            (None, Some(preset)) => {
+                let b = gtk4::Button::with_label("Download");
+                b.set_sensitive(!downloading);
+                let list_c = list_box.clone();
+                let runtime_c = Rc::clone(runtime);
+                let config_c = Arc::clone(config);
+                let db_c = Arc::clone(db);
+                let status_c = status.clone();
+                let menu_c = imported_menu.clone();
+                b.connect_clicked(move |b| {
+                    let Some(parent) = b.root().and_then(|r| r.downcast::<gtk4::Window>().ok())
+                    else {
+                        return;
+                    };
+                    b.set_sensitive(false);
+                    b.set_label("Downloading...");
+                    let list_d = list_c.clone();
+                    let runtime_d = Rc::clone(&runtime_c);
+                    let config_d = Arc::clone(&config_c);
+                    let db_d = Arc::clone(&db_c);
+                    let status_d = status_c.clone();
+                    let menu_d = menu_c.clone();
+                    download_model_only(
+                        &parent,
+                        &runtime_c,
+                        &config_c,
+                        &db_c,
+                        preset,
+                        &status_c,
+                        move || {
+                            fill_model_list(
+                                &list_d, &runtime_d, &config_d, &db_d, &status_d, &menu_d,
+                            );
+                        },
+                    );
+                });
+                b
+            }
+            (None, None) => continue,
+        };
+        button.set_valign(gtk4::Align::Center);
+        row.append(&button);
+        list_box.append(&row);
     }
 }
 
+/// Pick a ggml file in a file chooser and import it.
+fn choose_model_file(
+    parent: &gtk4::Window,
+    db: &Arc<Mutex<Db>>,
+    status: &gtk4::Label,
+    menu: &gtk4::gio::Menu,
+    mode_action: &gtk4::gio::SimpleAction,
+) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Import Model"),
+        Some(parent),
+        gtk4::FileChooserAction::Open,
+        Some("Import"),
+        Some("Cancel"),
+    );
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("ggml models (.bin)"));
+    filter.add_pattern("*.bin");
+    chooser.add_filter(&filter);
+
+    // The dialog closes once its last reference is dropped, so hold one
+    // until it answers
+    let keep: Rc<RefCell<Option<gtk4::FileChooserNative>>> = Rc::new(RefCell::new(None));
+    let keep_c = Rc::clone(&keep);
+    let db = Arc::clone(db);
+    let status = status.clone();
+    let menu = menu.clone();
+    let mode_action = mode_action.clone();
+    chooser.connect_response(move |chooser, response| {
+        if response == gtk4::ResponseType::Accept
+            && let Some(path) = chooser.file().and_then(|f| f.path())
+        {
+            import_model(&path, &db, &status, &menu, &mode_action);
+        }
+        chooser.destroy();
+        keep_c.borrow_mut().take();
+    });
+    chooser.show();
+    *keep.borrow_mut() = Some(chooser);
+}
+
 fn load_whisper_model(
     runtime: &Rc<RefCell<RuntimeState>>,
     model_path: &std::path::Path,
@@ -1667,8 +3890,16 @@ fn load_whisper_model(
     glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
         match rx.try_recv() {
             Ok(Ok(whisper)) => {
-                runtime_c.borrow_mut().local_whisper = Some(whisper);
-                show_status(&st, "Local mode ready");
+                let mut rt = runtime_c.borrow_mut();
+                rt.local_whisper = Some(whisper);
+                // Translation stays on for the API providers, but this model
+                // would only transcribe
+                if rt.translate && english_only_local(&rt) {
+                    show_status(&st, "Local mode ready, English-only: no translation");
+                } else {
+                    show_status(&st, "Local mode ready");
+                }
+                drop(rt);
                 let st2 = st.clone();
                 glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
                     hide_status(&st2);
@@ -1685,47 +3916,211 @@ fn load_whisper_model(
                     rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
                     rt.api_model = config::API_PRESETS[0].default_model.to_string();
                 }
-                action_c.set_state(&"groq".to_variant());
-                show_status(&st, "Model load failed");
-                let st2 = st.clone();
-                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
-                    hide_status(&st2);
-                });
-                glib::ControlFlow::Break
-            }
-            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
-            Err(_) => {
-                {
-                    let mut rt = runtime_c.borrow_mut();
-                    rt.active_service = TranscriptionService::Api;
-                    rt.active_provider = "groq".to_string();
-                    rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
-                    rt.api_model = config::API_PRESETS[0].default_model.to_string();
+                action_c.set_state(&"groq".to_variant());
+                show_status(&st, "Model load failed");
+                let st2 = st.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st2);
+                });
+                glib::ControlFlow::Break
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(_) => {
+                {
+                    let mut rt = runtime_c.borrow_mut();
+                    rt.active_service = TranscriptionService::Api;
+                    rt.active_provider = "groq".to_string();
+                    rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
+                    rt.api_model = config::API_PRESETS[0].default_model.to_string();
+                }
+                action_c.set_state(&"groq".to_variant());
+                show_status(&st, "Model load failed");
+                let st2 = st.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st2);
+                });
+                glib::ControlFlow::Break
+            }
+        }
+    });
+}
+
+/// Download progress messages sent from the background thread
+enum DownloadMsg {
+    Progress(u64, Option<u64>), // downloaded, total
+    StepLabel(String),          // update the label text
+    Done,
+    Error(String),
+}
+
+/// How a download ended.
+enum DownloadEnd {
+    Done,
+    Cancelled,
+    Failed(String),
+}
+
+/// Progress window shared by model and voice downloads: the current step,
+/// a progress bar, speed and time left, and a Cancel button.
+struct DownloadDialog {
+    window: gtk4::Window,
+    step: gtk4::Label,
+    bar: gtk4::ProgressBar,
+    detail: gtk4::Label,
+    rate: crate::download::TransferRate,
+}
+
+impl DownloadDialog {
+    /// Open the window. Cancel sets `cancel`; the download stops at its
+    /// next chunk and deletes its `.part` file.
+    fn new(
+        parent: &gtk4::Window,
+        title: &str,
+        step: &str,
+        cancel: &Arc<std::sync::atomic::AtomicBool>,
+    ) -> Self {
+        // Not modal, so the rest of the app stays usable meanwhile
+        let window = gtk4::Window::builder()
+            .title(title)
+            .transient_for(parent)
+            .default_width(340)
+            .resizable(false)
+            .deletable(false)
+            .build();
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+        vbox.set_margin_top(20);
+        vbox.set_margin_bottom(20);
+        vbox.set_margin_start(20);
+        vbox.set_margin_end(20);
+
+        let step = gtk4::Label::new(Some(step));
+        step.set_wrap(true);
+        let bar = gtk4::ProgressBar::new();
+        bar.set_show_text(true);
+        bar.set_text(Some("Starting..."));
+        let detail = gtk4::Label::new(None);
+        detail.set_opacity(0.6);
+
+        let cancel_btn = gtk4::Button::with_label("Cancel");
+        cancel_btn.set_halign(gtk4::Align::End);
+        let cancel_c = Arc::clone(cancel);
+        let step_c = step.clone();
+        cancel_btn.connect_clicked(move |b| {
+            cancel_c.store(true, std::sync::atomic::Ordering::Relaxed);
+            b.set_sensitive(false);
+            step_c.set_label("Cancelling...");
+        });
+
+        vbox.append(&step);
+        vbox.append(&bar);
+        vbox.append(&detail);
+        vbox.append(&cancel_btn);
+        window.set_child(Some(&vbox));
+        window.present();
+
+        Self {
+            window,
+            step,
+            bar,
+            detail,
+            rate: Default::default(),
+        }
+    }
+
+    fn show_progress(&mut self, downloaded: u64, total: Option<u64>) {
+        self.rate.record(std::time::Instant::now(), downloaded);
+        let dl_mb = downloaded as f64 / (1024.0 * 1024.0);
+        match total.filter(|&t| t > 0) {
+            Some(total) => {
+                self.bar.set_fraction(downloaded as f64 / total as f64);
+                let total_mb = total as f64 / (1024.0 * 1024.0);
+                self.bar
+                    .set_text(Some(&format!("{dl_mb:.1} / {total_mb:.1} MB")));
+            }
+            None => {
+                self.bar.pulse();
+                self.bar.set_text(Some(&format!("{dl_mb:.1} MB")));
+            }
+        }
+        self.detail.set_label(&crate::download::progress_detail(
+            downloaded,
+            total,
+            self.rate.bytes_per_sec(),
+        ));
+    }
+}
+
+/// Register a new download so `cancel-download` can reach it, and return
+/// its cancel flag.
+fn start_download(runtime: &Rc<RefCell<RuntimeState>>) -> Arc<std::sync::atomic::AtomicBool> {
+    let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    runtime
+        .borrow_mut()
+        .active_downloads
+        .push(Arc::clone(&cancel));
+    cancel
+}
+
+/// Show the messages from a download thread in `dialog` until it ends,
+/// then close the dialog and call `on_end`.
+fn watch_download(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    cancel: Arc<std::sync::atomic::AtomicBool>,
+    rx: std::sync::mpsc::Receiver<DownloadMsg>,
+    mut dialog: DownloadDialog,
+    on_end: impl FnOnce(DownloadEnd) + 'static,
+) {
+    let runtime_c = Rc::clone(runtime);
+    let mut on_end = Some(on_end);
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        let mut last_progress = None;
+        let mut end = None;
+        while let Ok(msg) = rx.try_recv() {
+            match msg {
+                DownloadMsg::Progress(downloaded, total) => {
+                    last_progress = Some((downloaded, total));
+                }
+                DownloadMsg::StepLabel(label) => {
+                    if !cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                        dialog.step.set_label(&label);
+                    }
                 }
-                action_c.set_state(&"groq".to_variant());
-                show_status(&st, "Model load failed");
-                let st2 = st.clone();
-                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
-                    hide_status(&st2);
-                });
-                glib::ControlFlow::Break
+                DownloadMsg::Done => end = Some(DownloadEnd::Done),
+                DownloadMsg::Error(_) if cancel.load(std::sync::atomic::Ordering::Relaxed) => {
+                    end = Some(DownloadEnd::Cancelled);
+                }
+                DownloadMsg::Error(e) => end = Some(DownloadEnd::Failed(e)),
             }
         }
-    });
-}
+        if let Some((downloaded, total)) = last_progress {
+            dialog.show_progress(downloaded, total);
+        }
 
-/// Download progress messages sent from the background thread
-enum DownloadMsg {
-    Progress(u64, Option<u64>), // downloaded, total
-    StepLabel(String),          // update the label text
-    Done,
-    Error(String),
+        let Some(end) = end else {
+            return glib::ControlFlow::Continue;
+        };
+        runtime_c
+            .borrow_mut()
+            .active_downloads
+            .retain(|c| !Arc::ptr_eq(c, &cancel));
+        dialog.window.close();
+        if let Some(on_end) = on_end.take() {
+            on_end(end);
+        }
+        glib::ControlFlow::Break
+    });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn download_and_load_model(
+    window: &gtk4::ApplicationWindow,
     runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
     model_path: &std::path::Path,
     url: &str,
+    checksum: crate::download::Checksum,
     action: &gtk4::gio::SimpleAction,
     status: &gtk4::Label,
 ) {
@@ -1733,131 +4128,106 @@ fn download_and_load_model(
 
     show_status(status, "Downloading model...");
 
+    let loaded_model_path = model_path.to_path_buf();
+    let cancel = start_download(runtime);
+    let name = model_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dialog = DownloadDialog::new(
+        window.upcast_ref(),
+        "Downloading Model",
+        &format!("Downloading {name}..."),
+        &cancel,
+    );
+    let rx = spawn_model_download(url, model_path, checksum, &cancel);
+
+    let runtime_c = Rc::clone(runtime);
+    let config_c = Arc::clone(config);
+    let db_c = Arc::clone(db);
+    let action_c = action.clone();
+    let st = status.clone();
+    watch_download(runtime, cancel, rx, dialog, move |end| {
+        let label = match end {
+            DownloadEnd::Done => {
+                runtime_c.borrow_mut().downloading = false;
+                // The new file may push the others over the disk budget
+                let active = runtime_c.borrow().active_provider.clone();
+                enforce_model_budget(&config_c, &db_c, &[active.as_str()]);
+                show_status(&st, "Loading model...");
+                // Now load the model
+                load_whisper_model(&runtime_c, &loaded_model_path, &action_c, &st);
+                return;
+            }
+            DownloadEnd::Cancelled => "Download cancelled",
+            DownloadEnd::Failed(e) => {
+                eprintln!("Model download failed: {e}");
+                "Download failed"
+            }
+        };
+        {
+            let mut rt = runtime_c.borrow_mut();
+            rt.downloading = false;
+            rt.active_service = TranscriptionService::Api;
+            rt.active_provider = "groq".to_string();
+            rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
+            rt.api_model = config::API_PRESETS[0].default_model.to_string();
+        }
+        action_c.set_state(&"groq".to_variant());
+        show_status(&st, label);
+        let st2 = st.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+            hide_status(&st2);
+        });
+    });
+}
+
+/// What a preset's download is checked against: its pinned SHA-256, else
+/// the one huggingface.co publishes for it (never a mirror's own).
+fn preset_checksum(preset: &config::LocalModelPreset) -> crate::download::Checksum {
+    crate::download::Checksum::pinned_or_published(
+        preset.pinned_sha256(),
+        preset.url(config::DEFAULT_HF_ENDPOINT),
+    )
+}
+
+/// Download a model file on a background thread, resuming an earlier
+/// partial download and verifying it before it is moved into place.
+fn spawn_model_download(
+    url: &str,
+    model_path: &std::path::Path,
+    checksum: crate::download::Checksum,
+    cancel: &Arc<std::sync::atomic::AtomicBool>,
+) -> std::sync::mpsc::Receiver<DownloadMsg> {
     let url = url.to_string();
     let model_path = model_path.to_path_buf();
-    let loaded_model_path = model_path.clone();
-    let part_path = model_path.with_extension("bin.part");
+    let cancel = Arc::clone(cancel);
 
     let (tx, rx) = std::sync::mpsc::channel::<DownloadMsg>();
 
     std::thread::spawn(move || {
-        let result = (|| -> Result<(), String> {
-            let resp = reqwest::blocking::Client::new()
-                .get(&url)
-                .send()
-                .map_err(|e| format!("Download request failed: {e}"))?;
-
-            if !resp.status().is_success() {
-                return Err(format!("Download failed: HTTP {}", resp.status()));
-            }
-
-            let total = resp.content_length();
-            let mut downloaded: u64 = 0;
-
-            let mut file = {
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::OpenOptionsExt;
-                    std::fs::OpenOptions::new()
-                        .write(true)
-                        .create(true)
-                        .truncate(true)
-                        .mode(0o600)
-                        .open(&part_path)
-                        .map_err(|e| format!("Failed to create file: {e}"))?
-                }
-                #[cfg(not(unix))]
-                {
-                    std::fs::File::create(&part_path)
-                        .map_err(|e| format!("Failed to create file: {e}"))?
-                }
-            };
-
-            use std::io::{Read, Write};
-            let mut reader = resp;
-            let mut buf = [0u8; 65536];
-            loop {
-                let n = reader
-                    .read(&mut buf)
-                    .map_err(|e| format!("Download read error: {e}"))?;
-                if n == 0 {
-                    break;
-                }
-                file.write_all(&buf[..n])
-                    .map_err(|e| format!("File write error: {e}"))?;
-                downloaded += n as u64;
+        let result = crate::download::fetch(
+            &url,
+            &model_path,
+            &checksum,
+            &cancel,
+            |downloaded, total| {
                 let _ = tx.send(DownloadMsg::Progress(downloaded, total));
-            }
-
-            // Rename .part → final path
-            std::fs::rename(&part_path, &model_path)
-                .map_err(|e| format!("Failed to rename model file: {e}"))?;
-
-            Ok(())
-        })();
-
+            },
+        );
         match result {
             Ok(()) => {
                 let _ = tx.send(DownloadMsg::Done);
             }
+            // Unless cancelled, the .part file is kept so the next attempt
+            // resumes it
             Err(e) => {
-                // Clean up partial file
-                let _ = std::fs::remove_file(&part_path);
                 let _ = tx.send(DownloadMsg::Error(e));
             }
         }
     });
 
-    let runtime_c = Rc::clone(runtime);
-    let action_c = action.clone();
-    let st = status.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(200), move || {
-        // Drain all pending messages, keep the last one
-        let mut last_msg = None;
-        while let Ok(msg) = rx.try_recv() {
-            last_msg = Some(msg);
-        }
-
-        match last_msg {
-            Some(DownloadMsg::Progress(downloaded, total)) => {
-                let dl_mb = downloaded as f64 / (1024.0 * 1024.0);
-                if let Some(t) = total {
-                    let total_mb = t as f64 / (1024.0 * 1024.0);
-                    show_status(&st, &format!("Downloading: {dl_mb:.0} / {total_mb:.0} MB"));
-                } else {
-                    show_status(&st, &format!("Downloading: {dl_mb:.0} MB"));
-                }
-                glib::ControlFlow::Continue
-            }
-            Some(DownloadMsg::Done) => {
-                runtime_c.borrow_mut().downloading = false;
-                show_status(&st, "Loading model...");
-                // Now load the model
-                load_whisper_model(&runtime_c, &loaded_model_path, &action_c, &st);
-                glib::ControlFlow::Break
-            }
-            Some(DownloadMsg::StepLabel(_)) => glib::ControlFlow::Continue,
-            Some(DownloadMsg::Error(e)) => {
-                eprintln!("Model download failed: {e}");
-                {
-                    let mut rt = runtime_c.borrow_mut();
-                    rt.downloading = false;
-                    rt.active_service = TranscriptionService::Api;
-                    rt.active_provider = "groq".to_string();
-                    rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
-                    rt.api_model = config::API_PRESETS[0].default_model.to_string();
-                }
-                action_c.set_state(&"groq".to_variant());
-                show_status(&st, "Download failed");
-                let st2 = st.clone();
-                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
-                    hide_status(&st2);
-                });
-                glib::ControlFlow::Break
-            }
-            None => glib::ControlFlow::Continue,
-        }
-    });
+    rx
 }
 
 fn save_window_position(win: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
@@ -1935,7 +4305,178 @@ fn position_window(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
     }
 }
 
-fn show_history_dialog(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+/// Write the stored segments of a history entry as subtitles, in the format
+/// the file extension names (`.vtt` for WebVTT, SRT otherwise).
+fn export_subtitles(db: &Arc<Mutex<Db>>, id: i64, path: &std::path::Path) -> Result<(), String> {
+    let segments = db
+        .lock()
+        .map_err(|_| "History unavailable".to_string())?
+        .segments(id)
+        .map_err(|e| format!("History lookup failed: {e}"))?;
+    write_subtitles(&segments, path)
+}
+
+/// Write segments as subtitles, in the format the file extension names
+/// (`.vtt` for WebVTT, SRT otherwise).
+fn write_subtitles(
+    segments: &[crate::transcript::Segment],
+    path: &std::path::Path,
+) -> Result<(), String> {
+    if segments.is_empty() {
+        return Err("No timings stored for this transcription".into());
+    }
+    let format = SubtitleFormat::from_path(path);
+    std::fs::write(path, subtitles(segments, format))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Ask where to save the subtitles of a history entry, then write them.
+fn choose_subtitle_file(parent: &gtk4::Window, db: &Arc<Mutex<Db>>, id: i64) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Export Subtitles"),
+        Some(parent),
+        gtk4::FileChooserAction::Save,
+        Some("Export"),
+        Some("Cancel"),
+    );
+    chooser.set_current_name(&format!("transcript-{id}.srt"));
+    for (name, pattern) in [("SubRip (.srt)", "*.srt"), ("WebVTT (.vtt)", "*.vtt")] {
+        let filter = gtk4::FileFilter::new();
+        filter.set_name(Some(name));
+        filter.add_pattern(pattern);
+        chooser.add_filter(&filter);
+    }
+
+    // The dialog closes once its last reference is dropped, so hold one
+    // until it answers
+    let keep: Rc<RefCell<Option<gtk4::FileChooserNative>>> = Rc::new(RefCell::new(None));
+    let keep_c = Rc::clone(&keep);
+    let db = Arc::clone(db);
+    chooser.connect_response(move |chooser, response| {
+        if response == gtk4::ResponseType::Accept
+            && let Some(path) = chooser.file().and_then(|f| f.path())
+            && let Err(e) = export_subtitles(&db, id, &path)
+        {
+            eprintln!("Subtitle export error: {e}");
+        }
+        chooser.destroy();
+        keep_c.borrow_mut().take();
+    });
+    chooser.show();
+    *keep.borrow_mut() = Some(chooser);
+}
+
+/// Refill the glossary window's term list from the database.
+fn fill_glossary_list(list_box: &gtk4::Box, db: &Arc<Mutex<Db>>) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+    let terms = db
+        .lock()
+        .ok()
+        .and_then(|d| d.glossary().ok())
+        .unwrap_or_default();
+    if terms.is_empty() {
+        let empty = gtk4::Label::new(Some("No terms yet."));
+        empty.set_opacity(0.6);
+        list_box.append(&empty);
+        return;
+    }
+    for term in terms {
+        let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        let label = gtk4::Label::new(Some(&term));
+        label.set_halign(gtk4::Align::Start);
+        label.set_hexpand(true);
+        label.set_selectable(true);
+        let remove = gtk4::Button::with_label("Remove");
+        row.append(&label);
+        row.append(&remove);
+        list_box.append(&row);
+
+        let db_rm = Arc::clone(db);
+        let list_rm = list_box.clone();
+        remove.connect_clicked(move |_| {
+            if let Ok(d) = db_rm.lock()
+                && let Err(e) = d.remove_glossary_term(&term)
+            {
+                eprintln!("Glossary remove error: {e}");
+            }
+            fill_glossary_list(&list_rm, &db_rm);
+        });
+    }
+}
+
+/// Manage the glossary: product names, surnames and other words the
+/// transcription should spell the way they are written here.
+fn show_glossary_dialog(window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    let dialog = gtk4::Window::builder()
+        .title("WhisperCrabs Glossary")
+        .transient_for(window)
+        .default_width(360)
+        .default_height(320)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let header = gtk4::Label::new(Some("Glossary"));
+    header.add_css_class("heading");
+    vbox.append(&header);
+
+    let hint = gtk4::Label::new(Some(
+        "Names and terms to spell exactly as written. They are sent with each recording.",
+    ));
+    hint.set_wrap(true);
+    hint.set_opacity(0.6);
+    vbox.append(&hint);
+
+    let add_row = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+    let entry = gtk4::Entry::new();
+    entry.set_placeholder_text(Some("e.g. WhisperCrabs"));
+    entry.set_hexpand(true);
+    let add = gtk4::Button::with_label("Add");
+    add_row.append(&entry);
+    add_row.append(&add);
+    vbox.append(&add_row);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    let list_box = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+    fill_glossary_list(&list_box, db);
+    scroll.set_child(Some(&list_box));
+    vbox.append(&scroll);
+
+    // Enter in the entry adds the term too
+    let db_add = Arc::clone(db);
+    let entry_add = entry.clone();
+    let list_add = list_box.clone();
+    let add_term = move || {
+        let term = entry_add.text().to_string();
+        if let Ok(d) = db_add.lock()
+            && let Err(e) = d.add_glossary_term(&term)
+        {
+            eprintln!("Glossary add error: {e}");
+        }
+        entry_add.set_text("");
+        fill_glossary_list(&list_add, &db_add);
+    };
+    let add_term = Rc::new(add_term);
+    let add_click = Rc::clone(&add_term);
+    add.connect_clicked(move |_| add_click());
+    entry.connect_activate(move |_| add_term());
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+fn show_history_dialog(
+    _window: &gtk4::ApplicationWindow,
+    db: &Arc<Mutex<Db>>,
+    retranscribe: Rc<dyn Fn(i64)>,
+) {
     let dialog = gtk4::Window::builder()
         .title("WhisperCrabs History")
         .default_width(400)
@@ -1956,9 +4497,12 @@ fn show_history_dialog(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
     scroll.set_vexpand(true);
 
     let list_box = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+    // Shared by all play buttons: starting one playback stops the previous
+    let playback_stop: Rc<RefCell<Option<Arc<std::sync::atomic::AtomicBool>>>> =
+        Rc::new(RefCell::new(None));
 
-    if let Ok(db) = db.lock()
-        && let Ok(entries) = db.recent(20)
+    if let Ok(conn) = db.lock()
+        && let Ok(entries) = conn.recent(20)
     {
         if entries.is_empty() {
             let empty = gtk4::Label::new(Some("No transcriptions yet."));
@@ -1966,7 +4510,11 @@ fn show_history_dialog(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
         } else {
             for entry in entries {
                 let row = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
-                let time = gtk4::Label::new(Some(&entry.created_at));
+                let when = match &entry.language {
+                    Some(code) => format!("{} · {}", entry.created_at, code.to_uppercase()),
+                    None => entry.created_at.clone(),
+                };
+                let time = gtk4::Label::new(Some(&when));
                 time.set_halign(gtk4::Align::Start);
                 time.set_opacity(0.6);
 
@@ -1978,6 +4526,71 @@ fn show_history_dialog(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
                 row.append(&time);
                 row.append(&text);
 
+                let controls = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+                if entry.has_audio || entry.has_segments {
+                    row.append(&controls);
+                }
+
+                if entry.has_segments {
+                    let export = gtk4::Button::with_label("Export Subtitles");
+                    controls.append(&export);
+                    let id = entry.id;
+                    let db_export = Arc::clone(db);
+                    let dialog_export = dialog.clone();
+                    export.connect_clicked(move |_| {
+                        choose_subtitle_file(&dialog_export, &db_export, id);
+                    });
+                }
+
+                if entry.has_audio {
+                    let play = gtk4::Button::with_label("Play");
+                    let redo = gtk4::Button::with_label("Re-transcribe");
+                    controls.prepend(&redo);
+                    controls.prepend(&play);
+
+                    let id = entry.id;
+                    let db_play = Arc::clone(db);
+                    let stop_slot = Rc::clone(&playback_stop);
+                    play.connect_clicked(move |play| {
+                        // Second click on a playing entry stops it
+                        if let Some(stop) = stop_slot.borrow_mut().take() {
+                            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+                            if play.label().as_deref() == Some("Stop") {
+                                return;
+                            }
+                        }
+                        let stored = db_play
+                            .lock()
+                            .ok()
+                            .and_then(|d| d.recording(id).ok().flatten());
+                        let samples = match stored.map(|a| crate::retention::decompress(&a)) {
+                            Some(Ok(samples)) => samples,
+                            Some(Err(e)) => {
+                                eprintln!("Playback error: {e}");
+                                return;
+                            }
+                            None => return,
+                        };
+                        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+                        *stop_slot.borrow_mut() = Some(Arc::clone(&stop));
+                        play.set_label("Stop");
+                        let play_done = play.clone();
+                        play_tts_audio(
+                            samples,
+                            crate::retention::RETAINED_SAMPLE_RATE,
+                            stop,
+                            move || play_done.set_label("Play"),
+                        );
+                    });
+
+                    let retranscribe = Rc::clone(&retranscribe);
+                    let dialog_ref = dialog.clone();
+                    redo.connect_clicked(move |_| {
+                        dialog_ref.close();
+                        retranscribe(id);
+                    });
+                }
+
                 let sep = gtk4::Separator::new(gtk4::Orientation::Horizontal);
                 list_box.append(&row);
                 list_box.append(&sep);
@@ -1988,6 +4601,14 @@ fn show_history_dialog(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
     scroll.set_child(Some(&list_box));
     vbox.append(&scroll);
 
+    // Closing the window stops playback
+    dialog.connect_close_request(move |_| {
+        if let Some(stop) = playback_stop.borrow_mut().take() {
+            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+        glib::Propagation::Proceed
+    });
+
     dialog.set_child(Some(&vbox));
     dialog.present();
 }
@@ -2057,7 +4678,7 @@ fn cleanup_old_voice(piper_dir: &std::path::Path, old_voice_id: &str) {
 #[allow(clippy::too_many_arguments)]
 fn download_tts_models(
     runtime: &Rc<RefCell<RuntimeState>>,
-    models_dir: &std::path::Path,
+    config: &Config,
     db: &Arc<Mutex<Db>>,
     action: &gtk4::gio::SimpleAction,
     read_cb_action: &gtk4::gio::SimpleAction,
@@ -2073,58 +4694,77 @@ fn download_tts_models(
         skip_venv
     );
 
-    let piper_dir = models_dir.join("piper");
+    let piper_dir = config.models_dir.join("piper");
     std::fs::create_dir_all(&piper_dir).ok();
 
-    // Build download dialog with progress bar
-    let dialog = gtk4::Window::builder()
-        .title("Downloading TTS")
-        .transient_for(parent)
-        .modal(true)
-        .default_width(340)
-        .default_height(120)
-        .resizable(false)
-        .deletable(false)
-        .build();
-
-    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
-    vbox.set_margin_top(20);
-    vbox.set_margin_bottom(20);
-    vbox.set_margin_start(20);
-    vbox.set_margin_end(20);
-
     let initial_label = if skip_venv {
         format!("Downloading voice: {}...", voice.label)
     } else {
         "Setting up Piper TTS engine...".to_string()
     };
-    let progress_label = gtk4::Label::new(Some(&initial_label));
-    let progress_bar = gtk4::ProgressBar::new();
-    progress_bar.set_show_text(true);
-    progress_bar.set_text(Some("Starting..."));
-
-    vbox.append(&progress_label);
-    vbox.append(&progress_bar);
-    dialog.set_child(Some(&vbox));
-    dialog.present();
+    let cancel = start_download(runtime);
+    let dialog = DownloadDialog::new(
+        parent.upcast_ref(),
+        "Downloading TTS",
+        &initial_label,
+        &cancel,
+    );
 
     let (tx, rx) = std::sync::mpsc::channel::<DownloadMsg>();
     let sdir = piper_dir.clone();
     let voice_id = voice.id.to_string();
-    let onnx_url = voice.onnx_url();
-    let config_url = voice.config_url();
+    let onnx_url = voice.onnx_url(&config.hf_endpoint);
+    let config_url = voice.config_url(&config.hf_endpoint);
+    let onnx_checksum = crate::download::Checksum::pinned_or_published(
+        voice.onnx_pinned_sha256(),
+        voice.onnx_url(config::DEFAULT_HF_ENDPOINT),
+    );
+    let config_checksum = crate::download::Checksum::pinned_or_published(
+        voice.json_pinned_sha256(),
+        voice.config_url(config::DEFAULT_HF_ENDPOINT),
+    );
+    let cancel_c = Arc::clone(&cancel);
 
     std::thread::spawn(move || {
         // Step 1: Set up venv (if needed)
         if !skip_venv {
+            // Runs a setup command, killing it if the download is cancelled
             let run_cmd = |cmd: &str, args: &[&str]| -> Result<(), String> {
+                use std::io::Read;
+                if cancel_c.load(std::sync::atomic::Ordering::Relaxed) {
+                    return Err(crate::download::CANCELLED.into());
+                }
                 dbg_log!("[TTS] running: {cmd} {}", args.join(" "));
-                let output = std::process::Command::new(cmd)
+                let mut child = std::process::Command::new(cmd)
                     .args(args)
-                    .output()
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::piped())
+                    .spawn()
                     .map_err(|e| format!("run {cmd}: {e}"))?;
-                if !output.status.success() {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
+                // Drain stderr on the side so a chatty command can't fill the pipe
+                let mut stderr_pipe = child.stderr.take();
+                let stderr_reader = std::thread::spawn(move || {
+                    let mut stderr = String::new();
+                    if let Some(pipe) = stderr_pipe.as_mut() {
+                        let _ = pipe.read_to_string(&mut stderr);
+                    }
+                    stderr
+                });
+                let status = loop {
+                    if cancel_c.load(std::sync::atomic::Ordering::Relaxed) {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        dbg_log!("[TTS] {cmd} killed on cancel");
+                        return Err(crate::download::CANCELLED.into());
+                    }
+                    match child.try_wait() {
+                        Ok(Some(status)) => break status,
+                        Ok(None) => std::thread::sleep(std::time::Duration::from_millis(100)),
+                        Err(e) => return Err(format!("wait for {cmd}: {e}")),
+                    }
+                };
+                if !status.success() {
+                    let stderr = stderr_reader.join().unwrap_or_default();
                     return Err(format!("{cmd} failed: {stderr}"));
                 }
                 Ok(())
@@ -2152,53 +4792,37 @@ fn download_tts_models(
             }
             dbg_log!("[TTS] piper-tts installed");
         }
+        if cancel_c.load(std::sync::atomic::Ordering::Relaxed) {
+            let _ = tx.send(DownloadMsg::Error(crate::download::CANCELLED.into()));
+            return;
+        }
 
         // Step 2: Download voice model files
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(600))
-            .build()
-            .expect("http client");
-
         let tx_ref = &tx;
-        let download_file = |url: &str, dest: &std::path::Path| -> Result<(), String> {
-            let part_path = dest.with_extension("part");
-            let resp = client
-                .get(url)
-                .send()
-                .map_err(|e| format!("Download: {e}"))?;
-            if !resp.status().is_success() {
-                return Err(format!("HTTP {}", resp.status()));
-            }
-            let content_len = resp.content_length();
-            let mut file = std::fs::File::create(&part_path).map_err(|e| format!("Create: {e}"))?;
-            use std::io::{Read, Write};
-            let mut reader = resp;
-            let mut buf = [0u8; 65536];
-            let mut downloaded: u64 = 0;
-            loop {
-                let n = reader.read(&mut buf).map_err(|e| format!("Read: {e}"))?;
-                if n == 0 {
-                    break;
-                }
-                file.write_all(&buf[..n])
-                    .map_err(|e| format!("Write: {e}"))?;
-                downloaded += n as u64;
-                let _ = tx_ref.send(DownloadMsg::Progress(downloaded, content_len));
-            }
-            std::fs::rename(&part_path, dest).map_err(|e| format!("Rename: {e}"))?;
-            Ok(())
+        let download_file = |url: &str,
+                             dest: &std::path::Path,
+                             checksum: &crate::download::Checksum|
+         -> Result<(), String> {
+            crate::download::fetch(url, dest, checksum, &cancel_c, |downloaded, total| {
+                let _ = tx_ref.send(DownloadMsg::Progress(downloaded, total));
+            })
         };
 
         let _ = tx.send(DownloadMsg::StepLabel("Downloading voice model...".into()));
         let onnx_dest = sdir.join(format!("{voice_id}.onnx"));
-        if let Err(e) = download_file(&onnx_url, &onnx_dest) {
+        if let Err(e) = download_file(&onnx_url, &onnx_dest, &onnx_checksum) {
             let _ = tx.send(DownloadMsg::Error(format!("voice model: {e}")));
             return;
         }
 
         let _ = tx.send(DownloadMsg::StepLabel("Downloading voice config...".into()));
         let config_dest = sdir.join(format!("{voice_id}.onnx.json"));
-        if let Err(e) = download_file(&config_url, &config_dest) {
+        if let Err(e) = download_file(&config_url, &config_dest, &config_checksum) {
+            // A voice model without its config is unusable, so a cancel
+            // doesn't leave it behind
+            if cancel_c.load(std::sync::atomic::Ordering::Relaxed) {
+                let _ = std::fs::remove_file(&onnx_dest);
+            }
             let _ = tx.send(DownloadMsg::Error(format!("voice config: {e}")));
             return;
         }
@@ -2213,56 +4837,11 @@ fn download_tts_models(
     let read_cb_c = read_cb_action.clone();
     let st = status.clone();
     let sdir = piper_dir;
-    let dialog_ref = dialog.clone();
-    let pbar = progress_bar.clone();
-    let plabel = progress_label.clone();
     let vid = voice.id.to_string();
 
-    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-        let mut last_progress = None;
-        let mut last_label = None;
-        let mut terminal = None;
-
-        while let Ok(msg) = rx.try_recv() {
-            match msg {
-                DownloadMsg::Progress(dl, total) => {
-                    last_progress = Some((dl, total));
-                }
-                DownloadMsg::StepLabel(s) => {
-                    last_label = Some(s);
-                }
-                DownloadMsg::Done => {
-                    terminal = Some(Ok(()));
-                }
-                DownloadMsg::Error(e) => {
-                    terminal = Some(Err(e));
-                }
-            }
-        }
-
-        if let Some(label) = last_label {
-            plabel.set_label(&label);
-        }
-
-        if let Some((downloaded, total)) = last_progress {
-            if let Some(total) = total {
-                if total > 0 {
-                    let frac = downloaded as f64 / total as f64;
-                    pbar.set_fraction(frac);
-                    let dl_mb = downloaded as f64 / 1_048_576.0;
-                    let total_mb = total as f64 / 1_048_576.0;
-                    pbar.set_text(Some(&format!("{dl_mb:.1} / {total_mb:.1} MB")));
-                }
-            } else {
-                pbar.pulse();
-                let dl_mb = downloaded as f64 / 1_048_576.0;
-                pbar.set_text(Some(&format!("{dl_mb:.1} MB")));
-            }
-        }
-
-        match terminal {
-            Some(Ok(())) => {
-                dialog_ref.close();
+    watch_download(runtime, cancel, rx, dialog, move |end| {
+        let label = match end {
+            DownloadEnd::Done => {
                 // Clean up previous voice files
                 if old_voice_id != vid {
                     cleanup_old_voice(&sdir, &old_voice_id);
@@ -2282,32 +4861,25 @@ fn download_tts_models(
                         read_cb_c.set_enabled(true);
                         action_c.set_state(&vid.to_variant());
                         hide_status(&st);
+                        return;
                     }
                     Err(e) => {
                         eprintln!("TTS load failed: {e}");
-                        runtime_c.borrow_mut().tts_downloading = false;
-                        show_status(&st, "TTS load failed");
-                        let st2 = st.clone();
-                        glib::timeout_add_local_once(
-                            std::time::Duration::from_secs(3),
-                            move || hide_status(&st2),
-                        );
+                        "TTS load failed"
                     }
                 }
-                glib::ControlFlow::Break
             }
-            Some(Err(e)) => {
-                dialog_ref.close();
+            DownloadEnd::Cancelled => "TTS download cancelled",
+            DownloadEnd::Failed(e) => {
                 eprintln!("TTS download failed: {e}");
-                runtime_c.borrow_mut().tts_downloading = false;
-                show_status(&st, "TTS download failed");
-                let st2 = st.clone();
-                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
-                    hide_status(&st2);
-                });
-                glib::ControlFlow::Break
+                "TTS download failed"
             }
-            None => glib::ControlFlow::Continue,
-        }
+        };
+        runtime_c.borrow_mut().tts_downloading = false;
+        show_status(&st, label);
+        let st2 = st.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+            hide_status(&st2);
+        });
     });
 }